@@ -0,0 +1,93 @@
+use std::io;
+
+/// Errors returned by the library's reading, writing and calculation
+/// functions, so callers can tell the failure modes apart.
+///
+/// Errors from the underlying I/O, CSV, JSON and date-parsing layers are
+/// wrapped transparently and display exactly as the source error does.
+#[derive(Debug, thiserror::Error)]
+pub enum GrqError {
+    /// A file could not be opened, read or written.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A score file or derived CSV could not be parsed or written.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    /// A JSON market-data, dividend or index file could not be parsed or
+    /// serialised.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// A date was not in the expected `YYYY-MM-DD` format.
+    #[error(transparent)]
+    ParseDate(#[from] chrono::ParseError),
+    /// A path built from a score file, index entry or ticker would escape its
+    /// data directory (or is empty).
+    #[error("{0}")]
+    UnsafePath(String),
+    /// A file could not be fetched over HTTP (see
+    /// [`crate::utils::read_index_json_from_url`]).
+    #[error("{0}")]
+    Http(String),
+    /// A write was asked of a location that can only be read, such as an
+    /// index published at a URL.
+    #[error("{0}")]
+    ReadOnly(String),
+    /// A score file listed in `index.json` does not exist on disk.
+    #[error("score file {0} is missing")]
+    ScoreFileMissing(String),
+    /// The market data needed for a calculation is not available.
+    #[error("{0}")]
+    MarketDataMissing(String),
+    /// The score date is later than the newest market data available, so
+    /// there is nothing to price the portfolio with yet.
+    #[error("score date {score_date} is after latest market data ({latest})")]
+    ScoreAfterMarketData {
+        /// The score date (`YYYY-MM-DD`).
+        score_date: String,
+        /// The newest date the loaded market data covers (`YYYY-MM-DD`).
+        latest: String,
+    },
+    /// A score file lists a ticker more than once and duplicates were not
+    /// allowed.
+    #[error(
+        "{file} lists {count} tickers more than once; pass --allow-duplicates to keep only the first occurrence"
+    )]
+    DuplicateTickers {
+        /// Path of the score file.
+        file: String,
+        /// Number of distinct tickers listed more than once.
+        count: usize,
+    },
+    /// A score file has a header but no stock rows, so there is no portfolio
+    /// to measure (rather than a genuine 0% return).
+    #[error("no stock records in {0}")]
+    NoStockRecords(String),
+    /// A hybrid projection was requested for a score that is already 90 days
+    /// old and has settled performance instead.
+    #[error("Score is already 90 days old, use regular performance calculation")]
+    ScoreSettled,
+    /// Malformed or unreadable price or dividend data was found while strict
+    /// mode (see [`crate::utils::DataOptions::strict`]) was on, where it would
+    /// otherwise have been skipped with a warning.
+    #[error("strict mode: {0}")]
+    StrictModeViolation(String),
+    /// A calculation option is outside the range it can be applied over.
+    #[error("{0}")]
+    InvalidOption(String),
+    /// Too few stocks had usable prices for the portfolio's average to be
+    /// representative (see [`crate::utils::PerformanceOptions::min_stocks`]).
+    #[error(
+        "only {included} stocks in the {score_date} portfolio have usable data, fewer than the minimum of {required}"
+    )]
+    TooFewStocks {
+        /// The score date (`YYYY-MM-DD`).
+        score_date: String,
+        /// Number of stocks with usable buy and current prices.
+        included: usize,
+        /// The minimum that was required.
+        required: usize,
+    },
+}
+
+/// Result type of the library's fallible functions.
+pub type Result<T, E = GrqError> = std::result::Result<T, E>;
@@ -4,15 +4,22 @@
 #![deny(unsafe_op_in_unsafe_fn)]
 //! Processes daily stock-score TSV files and computes portfolio performance.
 //!
-//! The crate exposes two modules:
+//! The crate exposes four modules:
 //!
+//! - [`error`] — the [`error::GrqError`] type returned by the fallible
+//!   library functions.
 //! - [`models`] — serde-backed data types for score records, market data,
 //!   dividends and the computed performance results.
 //! - [`utils`] — functions to read the score/market/dividend files, build the
 //!   derived CSVs and calculate 90-day and annualised portfolio performance.
+//! - [`report`] — a self-contained HTML summary of the scores index.
 
+/// Typed errors for library consumers.
+pub mod error;
 /// Data types shared across the crate (score records, market data, dividends
 /// and performance results).
 pub mod models;
+/// Self-contained HTML performance report rendering.
+pub mod report;
 /// File-reading, CSV-building and performance-calculation helpers.
 pub mod utils;
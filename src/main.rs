@@ -1,14 +1,57 @@
 use anyhow::{anyhow, Context, Result};
-use chrono::{NaiveDate, Utc};
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, ValueEnum};
+use grq_validation::error::GrqError;
+use grq_validation::models::{
+    AnnualizationBasis, DollarPortfolioValue, IndexData, MarketDataCsv, OverallSummary,
+    PortfolioPerformance, ScoreBucket, ScoreEntry, StockRecord,
+};
+use grq_validation::report::generate_html_report;
 use grq_validation::utils::{
-    build_score_file_path, create_dividend_csv_for_score_file,
-    create_market_data_long_csv_for_score_file, derive_csv_output_path,
-    ensure_market_data_repository, extract_ticker_codes_from_score_file, is_market_data_csv_empty,
-    read_index_json,
+    annualized_alpha, build_score_file_path, calculate_benchmark_return,
+    calculate_dollar_portfolio_value, calculate_horizon_returns,
+    calculate_horizon_returns_for_records, calculate_portfolio_performance_for_records,
+    calculate_ticker_history, compute_equity_curve,
+    create_dividend_csv_for_score_file_with_options,
+    create_market_data_long_csv_for_score_file_with_options, derive_csv_output_path_with_options,
+    diff_index, ensure_market_data_repository_with_options, existing_score_file_path,
+    extract_ticker_codes_from_score_file, find_missing_data, find_score_files,
+    is_market_data_csv_empty, keep_too_few_stocks, market_data_coverage, market_data_for_tickers,
+    month_name, month_number, overall_summary_as_of, paper_trades, projection_accuracy,
+    rank_against_benchmarks, read_index_json, read_index_json_from_url,
+    read_index_json_in_file_order, read_market_data_from_csv_with_options,
+    read_market_data_with_options, read_symbol_aliases, read_tsv_score_reader_checked,
+    rebuild_index, resample, score_bucket_returns, score_date_from_path, score_date_in_range,
+    score_file_path_for_date, score_return_correlation, set_max_concurrent_reads,
+    stocks_meeting_target, summarize_ticker_history, tickers_missing_from_csv,
+    validate_score_files, verify_generated_csv, write_equity_curve_csv, write_index_atomic,
+    write_paper_trade_csv, write_performance_detail, CsvKind, DampeningCurve, DataOptions,
+    EndPricePolicy, EntryPrice, ErrorPolicy, Freq, IndexUpdateOptions, PerformanceOptions,
+    ProjectionOptions, ReturnType, SystemClock, TargetMet, TradingCalendar,
+    DEFAULT_ACCURACY_ELAPSED_DAYS, DEFAULT_INDEX_DIFF_THRESHOLD, DEFAULT_MAX_BUY_GAP_DAYS,
+    DEFAULT_MAX_DATA_GAP_TRADING_DAYS, DEFAULT_MAX_INTRINSIC_VALUE_RATIO,
+    DEFAULT_PROJECTION_DAMPENING_MIN, MARKET_DATA_CACHE_ENV,
 };
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif_log_bridge::LogWrapper;
 use log::info;
-use std::path::Path;
+use notify_debouncer_full::new_debouncer;
+use notify_debouncer_full::notify::event::ModifyKind;
+use notify_debouncer_full::notify::{EventKind, RecursiveMode};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Shape of each log line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LogFormat {
+    /// env_logger's human-readable lines.
+    Text,
+    /// One JSON object per line with `timestamp`, `level`, `target` and
+    /// `message`.
+    Json,
+}
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -21,6 +64,62 @@ struct Args {
     #[arg(short, long)]
     verbose: bool,
 
+    /// Log only warnings and errors, so stdout carries just the results
+    #[arg(short, long, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Log each error with its full chain of causes rather than only the
+    /// top-level message
+    #[arg(long)]
+    verbose_errors: bool,
+
+    /// Share-price data repository to read from (default: $GRQ_MARKET_DATA,
+    /// else ../GRQ-shareprices2026Q2). Repeat it to merge several quarterly
+    /// archives, searched in order
+    #[arg(long, value_name = "DIR")]
+    market_data_path: Vec<String>,
+
+    /// Cache each ticker's parsed market-data window here as a binary file,
+    /// reused until the source JSON changes (default: $GRQ_MARKET_DATA_CACHE,
+    /// else no cache)
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// Read market data from the JSON archives only, ignoring any cache
+    /// directory
+    #[arg(long, conflicts_with = "cache_dir")]
+    no_cache: bool,
+
+    /// Flat `ticker,date,close` CSV to read share prices from instead of the
+    /// JSON repository
+    #[arg(long, value_name = "PATH", conflicts_with = "market_data_path")]
+    market_data_csv: Option<PathBuf>,
+
+    /// JSON object of renamed symbols, old to new (e.g. `{"FB": "META"}`);
+    /// reading either symbol's prices stitches both histories together
+    #[arg(long, value_name = "PATH")]
+    symbol_aliases: Option<PathBuf>,
+
+    /// Dividend data repository to read from (default: $GRQ_DIVIDENDS, else
+    /// ../GRQ-dividends)
+    #[arg(long, value_name = "DIR")]
+    dividend_data_path: Option<String>,
+
+    /// Write generated market-data and dividend CSVs and performance detail
+    /// JSON under this directory, mirroring `scores/YYYY/Month/`, instead of
+    /// beside each score file; they are read back from there too
+    #[arg(long, value_name = "DIR")]
+    artifacts_dir: Option<PathBuf>,
+
+    /// Log line format: `text`, or `json` for one object per line
+    #[arg(long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+
+    /// Show a progress bar over the score files being processed (log lines
+    /// are printed above it)
+    #[arg(long)]
+    progress: bool,
+
     /// Process all score files, including those more than 180 days old
     #[arg(long)]
     process_all: bool,
@@ -33,24 +132,1250 @@ struct Args {
     #[arg(long)]
     calculate_performance: bool,
 
+    /// With --calculate-performance, skip dates whose settled performance is
+    /// already recorded and only recompute missing or still-moving figures
+    #[arg(long)]
+    incremental: bool,
+
+    /// Lint every score file (parse errors, invalid symbols, out-of-range
+    /// scores) without computing performance; exits non-zero on any problem
+    #[arg(long)]
+    validate_only: bool,
+
+    /// With --validate-only, warn about a stock whose basic and adjusted
+    /// intrinsic values differ by more than this many times or have opposite
+    /// signs
+    #[arg(long, default_value_t = DEFAULT_MAX_INTRINSIC_VALUE_RATIO, value_name = "RATIO")]
+    max_intrinsic_ratio: f64,
+
     /// Process a specific date (format: YYYY-MM-DD)
     #[arg(long)]
     date: Option<String>,
+
+    /// Report one ticker's 90-day return in every score file that picked it
+    /// (a full ticker such as NYSE:SEM, or a bare symbol on any exchange)
+    #[arg(long, value_name = "SYMBOL")]
+    ticker: Option<String>,
+
+    /// Print the first and last dates, number of data points and any gaps of
+    /// more than five trading days in a ticker's market data, then exit
+    #[arg(long, value_name = "SYMBOL")]
+    inspect_ticker: Option<String>,
+
+    /// Comma-separated benchmark symbols (e.g. SPY,XLK,XLE) to compare a
+    /// settled --date portfolio against over the same 90-day window
+    #[arg(long, value_delimiter = ',')]
+    benchmark: Vec<String>,
+
+    /// Maximum number of market-data files read at the same time (defaults to
+    /// the CPU count)
+    #[arg(long)]
+    max_concurrent_reads: Option<usize>,
+
+    /// Check each generated market-data and dividend CSV (header, column
+    /// count, dates and numeric values) right after it is written, logging any
+    /// problems and failing the run if there were any
+    #[arg(long)]
+    verify: bool,
+
+    /// Also write the full per-stock performance to
+    /// `<year>/<month>/<day>-performance.json` beside each processed score file
+    #[arg(long)]
+    write_detail: bool,
+
+    /// With --date, write the equal-weighted portfolio value on each trading
+    /// day of the window (starting at 100) to this CSV
+    #[arg(long, value_name = "CSV")]
+    equity_curve: Option<String>,
+
+    /// Sampling of written series such as --equity-curve: `daily`, or
+    /// `weekly`/`monthly` to keep only the last value of each week or month
+    #[arg(long, default_value = "daily", value_name = "FREQ")]
+    resample: Freq,
+
+    /// Write a self-contained HTML performance report of the scores index to
+    /// this path at the end of the run
+    #[arg(long, value_name = "PATH")]
+    html_report: Option<String>,
+
+    /// Exclude a stock whose first close is more than this many days after
+    /// the score date (a data hole rather than a weekend or holiday)
+    #[arg(long, default_value_t = DEFAULT_MAX_BUY_GAP_DAYS)]
+    max_buy_gap_days: u32,
+
+    /// Accept score files that list a ticker more than once, keeping only its
+    /// first occurrence (duplicates are otherwise an error)
+    #[arg(long)]
+    allow_duplicates: bool,
+
+    /// Exit with an error listing every (date, ticker) whose price data is
+    /// missing or whose dividend data is unreadable, instead of skipping them
+    #[arg(long)]
+    fail_on_missing_data: bool,
+
+    /// Fail the run on a malformed close price or dividend amount, or a price
+    /// or dividend file that cannot be read, instead of warning and writing
+    /// partial CSVs
+    #[arg(long)]
+    strict: bool,
+
+    /// Print the all-time summary of completed score files from index.json
+    /// and exit
+    #[arg(long)]
+    summary: bool,
+
+    /// Read index.json from this URL (e.g. a published site's
+    /// `https://.../scores/index.json`) instead of the docs tree, for the
+    /// read-only --summary, --diff and --html-report; nothing is written back
+    #[arg(long, value_name = "URL")]
+    index_url: Option<String>,
+
+    /// Rewrite index.json from the score files under docs/scores, keeping the
+    /// performance figures of dates already in it, and exit
+    #[arg(long)]
+    rebuild_index: bool,
+
+    /// Replay the hybrid projection of every settled score file at several
+    /// days after its score date and print its error against the realised
+    /// 90-day return, then exit
+    #[arg(long)]
+    projection_accuracy: bool,
+
+    /// Compare this earlier index.json against the current one and list the
+    /// dates whose performance figures changed
+    #[arg(long, value_name = "OLD_INDEX")]
+    diff: Option<String>,
+
+    /// Smallest change, in percentage points, that --diff reports
+    #[arg(long, default_value_t = DEFAULT_INDEX_DIFF_THRESHOLD)]
+    diff_threshold: f64,
+
+    /// Entry price for each stock: `close` buys at the score-date close,
+    /// `next_open` at the open of the first trading day after the score date
+    #[arg(long, default_value = "close", value_name = "PRICE")]
+    entry_price: EntryPrice,
+
+    /// Close each stock is valued at when its 90-day end date did not trade:
+    /// `latest` takes the latest close in the window, `nearest:DAYS` the
+    /// closest one at most DAYS (up to 366) either side of the end date
+    /// (`exact` is `nearest:0`), excluding the stock when there is none
+    #[arg(long, default_value = "latest", value_name = "POLICY")]
+    end_price: EndPricePolicy,
+
+    /// Headline 90-day return: `total` includes dividends, `price` is the
+    /// price change alone
+    #[arg(long, default_value = "total", value_name = "TYPE")]
+    return_type: ReturnType,
+
+    /// What a batch run does when a score file fails: `skip` logs it and moves
+    /// on, `retry` tries the failing step again before skipping, `fail-fast`
+    /// aborts the run
+    #[arg(long = "on-error", default_value = "skip", value_name = "POLICY")]
+    error_policy: ErrorPolicy,
+
+    /// Days the 90-day return is annualised over: `calendar` compounds the
+    /// calendar days elapsed over 365.25 a year, `trading` the trading days
+    /// elapsed (skipping any --holiday) over 252 a year
+    #[arg(long, default_value = "calendar", value_name = "BASIS")]
+    annualization_basis: AnnualizationBasis,
+
+    /// Fewest stocks with usable price data a portfolio needs before its
+    /// performance is reported; a smaller one is left unset in index.json
+    #[arg(long, default_value_t = 0, value_name = "N")]
+    min_stocks: usize,
+
+    /// Annual risk-free rate, as a percentage, subtracted from the portfolio's
+    /// return in the Sharpe-style ratio
+    #[arg(long, default_value_t = 0.0)]
+    risk_free_rate: f64,
+
+    /// Dampening curve for projections of scores under 90 days old: `linear`
+    /// ramps to no dampening at 90 days, `step` is the original fixed steps
+    #[arg(long, default_value = "linear", value_name = "CURVE")]
+    projection_dampening: DampeningCurve,
+
+    /// Dampening factor at day zero for the linear projection curve (0 to 1)
+    #[arg(long, default_value_t = DEFAULT_PROJECTION_DAMPENING_MIN)]
+    projection_dampening_min: f64,
+
+    /// Reinvest dividends at the ex-date close instead of adding them as cash
+    #[arg(long)]
+    reinvest: bool,
+
+    /// Divide quoted prices by DIVISOR for an exchange or ticker, e.g. LON=100
+    /// (repeatable; LON defaults to 100 because London quotes are in pence)
+    #[arg(long = "price-divisor", value_name = "EXCHANGE=DIVISOR", value_parser = parse_price_divisor)]
+    price_divisors: Vec<(String, f64)>,
+
+    /// Exchange rate for dividends paid in another currency than the stock
+    /// trades in, as units of TO per FROM, e.g. EUR/USD=1.08 (repeatable)
+    #[arg(long = "dividend-fx", value_name = "FROM/TO=RATE", value_parser = parse_dividend_fx)]
+    dividend_fx_rates: Vec<(String, f64)>,
+
+    /// Run as if today were this date (YYYY-MM-DD): projections ignore later
+    /// prices and dividends, and score ages are measured to it
+    #[arg(long, value_parser = parse_iso_date, value_name = "DATE")]
+    as_of: Option<NaiveDate>,
+
+    /// Project young scores over the trading days (weekdays less any
+    /// --holiday) left in the 90-day window instead of calendar days
+    #[arg(long)]
+    trading_days: bool,
+
+    /// Market holiday to skip when projecting over trading days, as
+    /// YYYY-MM-DD (repeatable; implies --trading-days)
+    #[arg(long = "holiday", value_parser = parse_iso_date, value_name = "DATE")]
+    holidays: Vec<NaiveDate>,
+
+    /// Only batch-process score files dated on or after this date (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_iso_date)]
+    since: Option<NaiveDate>,
+
+    /// Only batch-process score files dated on or before this date (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_iso_date)]
+    until: Option<NaiveDate>,
+
+    /// Read a score TSV from stdin instead of the docs tree and print its
+    /// performance as of --score-date; nothing is written
+    #[arg(long, requires = "score_date")]
+    stdin: bool,
+
+    /// Score date of the TSV read with --stdin (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_iso_date, requires = "stdin")]
+    score_date: Option<NaiveDate>,
+
+    /// Batch-process only the first N of the selected score files, after the
+    /// age and --since/--until filters (for quick smoke tests)
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Total portfolio size in dollars for --date: the part not put into the
+    /// positions sized in Notes counts as cash earning 0%, giving a blended
+    /// return
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_total_capital)]
+    total_capital: Option<f64>,
+
+    /// With --date, write the picks as a `Symbol,Quantity,Price,Date` broker
+    /// import CSV to this path, buying the share counts sized in Notes at the
+    /// buy price
+    #[arg(long, value_name = "CSV", requires = "date")]
+    paper_trade: Option<String>,
+
+    /// With --paper-trade, split this many dollars equally across the picks
+    /// instead of using the share counts in Notes
+    #[arg(long, value_name = "AMOUNT", value_parser = parse_total_capital, requires = "paper_trade")]
+    capital: Option<f64>,
+
+    /// Keep running and, whenever a score TSV under docs/scores is saved,
+    /// regenerate its CSVs and recompute its index.json entry
+    #[arg(long, conflicts_with = "as_of")]
+    watch: bool,
+
+    /// With --date or --stdin, list only the stocks whose price met their
+    /// target: `at-end` (the default) by the end of the window, `any-time` at
+    /// any point in it. A projection has no intraday highs, so both judge it
+    /// by the latest close
+    #[arg(
+        long,
+        value_name = "WHEN",
+        num_args = 0..=1,
+        default_missing_value = "at-end"
+    )]
+    only_winners_vs_target: Option<TargetMet>,
+}
+
+/// Parses a `--since` / `--until` value in `YYYY-MM-DD` format.
+fn parse_iso_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|e| format!("invalid date '{value}' (expected YYYY-MM-DD): {e}"))
+}
+
+/// Parses a `--total-capital` or `--capital` amount, such as `100000` or
+/// `$100,000`.
+fn parse_total_capital(value: &str) -> Result<f64, String> {
+    let amount: f64 = value
+        .trim()
+        .replace(['$', ','], "")
+        .parse()
+        .map_err(|e| format!("invalid amount '{value}': {e}"))?;
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(format!("expected a positive amount, got '{value}'"));
+    }
+    Ok(amount)
+}
+
+/// Parses a `--price-divisor` value of the form `EXCHANGE=DIVISOR`.
+fn parse_price_divisor(value: &str) -> Result<(String, f64), String> {
+    let (key, divisor) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected EXCHANGE=DIVISOR, got '{value}'"))?;
+    let divisor: f64 = divisor
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid divisor '{divisor}': {e}"))?;
+    if key.trim().is_empty() || !divisor.is_finite() || divisor <= 0.0 {
+        return Err(format!(
+            "expected a non-empty exchange and a positive divisor, got '{value}'"
+        ));
+    }
+    Ok((key.trim().to_string(), divisor))
+}
+
+/// Parses a `--dividend-fx` value of the form `FROM/TO=RATE`.
+fn parse_dividend_fx(value: &str) -> Result<(String, f64), String> {
+    let (pair, rate) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected FROM/TO=RATE, got '{value}'"))?;
+    let rate: f64 = rate
+        .trim()
+        .parse()
+        .map_err(|e| format!("invalid rate '{rate}': {e}"))?;
+    let pair = pair.trim().to_ascii_uppercase();
+    let valid_pair = pair
+        .split_once('/')
+        .is_some_and(|(from, to)| !from.is_empty() && !to.is_empty());
+    if !valid_pair || !rate.is_finite() || rate <= 0.0 {
+        return Err(format!(
+            "expected a FROM/TO currency pair and a positive rate, got '{value}'"
+        ));
+    }
+    Ok((pair, rate))
+}
+
+/// Prints the dollar-weighted return on `total_capital`, with whatever the
+/// sized positions leave uninvested held as cash at 0%.
+fn print_blended_return(dollar_value: &DollarPortfolioValue, total_capital: f64) {
+    if dollar_value.positions == 0 {
+        log::warn!("Ignoring --total-capital: no positions are sized in the Notes");
+        return;
+    }
+    if dollar_value.start_value > total_capital {
+        log::warn!(
+            "Sized positions (${:.2}) exceed --total-capital (${total_capital:.2})",
+            dollar_value.start_value
+        );
+    }
+    let invested_percent = dollar_value.invested_fraction(total_capital) * 100.0;
+    println!(
+        "Blended Return: {:+.2}% on ${total_capital:.2} total capital \
+         ({invested_percent:.1}% invested, {:.1}% cash)",
+        dollar_value.blended_return_percent(total_capital),
+        (100.0 - invested_percent).max(0.0)
+    );
+}
+
+/// How long the score directory must be quiet before a burst of file events
+/// (an editor's save is often several) is handled as one change.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches `docs_path/scores` until interrupted. Each time score TSVs are
+/// saved, their market-data and dividend CSVs are regenerated and only their
+/// index.json entries are recomputed. Failures are logged and watching goes on.
+fn watch_score_files(
+    docs_path: &str,
+    index_update_options: &IndexUpdateOptions,
+    verbose_errors: bool,
+) -> Result<()> {
+    let scores_dir = Path::new(docs_path).join("scores");
+    let (events_tx, events_rx) = std::sync::mpsc::channel();
+    let mut debouncer =
+        new_debouncer(WATCH_DEBOUNCE, None, events_tx).context("starting the file watcher")?;
+    debouncer
+        .watch(&scores_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("watching {}", scores_dir.display()))?;
+    info!(
+        "Watching {} for score file changes (Ctrl-C to stop)",
+        scores_dir.display()
+    );
+
+    for events in events_rx {
+        let events = match events {
+            Ok(events) => events,
+            Err(errors) => {
+                for e in &errors {
+                    log::warn!("File watcher error: {}", format_error(e, verbose_errors));
+                }
+                continue;
+            }
+        };
+        // Only written score files matter: reprocessing reads the TSV, and
+        // writes CSVs and index.json, all of which raise events of their own
+        // that must not trigger another pass.
+        let changed: BTreeMap<NaiveDate, PathBuf> = events
+            .into_iter()
+            .filter(|event| match event.kind {
+                EventKind::Create(_) => true,
+                EventKind::Modify(kind) => !matches!(kind, ModifyKind::Metadata(_)),
+                _ => false,
+            })
+            .flat_map(|event| event.event.paths)
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tsv") && path.is_file())
+            .filter_map(|path| score_date_from_path(&path).map(|date| (date, path)))
+            .collect();
+        if !changed.is_empty() {
+            rerun_score_dates(docs_path, index_update_options, &changed, verbose_errors);
+        }
+    }
+    Ok(())
+}
+
+/// Regenerates the CSVs for each changed score file, then recomputes the
+/// index.json entries for just those dates.
+fn rerun_score_dates(
+    docs_path: &str,
+    index_update_options: &IndexUpdateOptions,
+    changed: &BTreeMap<NaiveDate, PathBuf>,
+    verbose_errors: bool,
+) {
+    for (date, path) in changed {
+        let score_file_path = path.to_string_lossy();
+        let date = date.format("%Y-%m-%d").to_string();
+        info!("{score_file_path} changed; reprocessing {date}");
+        let ticker_codes = match extract_ticker_codes_from_score_file(&score_file_path) {
+            Ok(ticker_codes) => ticker_codes,
+            Err(e) => {
+                log::error!(
+                    "Failed to read ticker codes from {score_file_path}: {}",
+                    format_error(&e, verbose_errors)
+                );
+                continue;
+            }
+        };
+        let data = &index_update_options.performance.data;
+        if let Err(e) = create_market_data_long_csv_for_score_file_with_options(
+            &score_file_path,
+            &ticker_codes,
+            &date,
+            None,
+            data,
+        ) {
+            log::error!(
+                "Failed to create market data CSV: {}",
+                format_error(&e, verbose_errors)
+            );
+        }
+        if let Err(e) = create_dividend_csv_for_score_file_with_options(
+            &score_file_path,
+            &ticker_codes,
+            &date,
+            data,
+        ) {
+            log::error!(
+                "Failed to create dividend CSV: {}",
+                format_error(&e, verbose_errors)
+            );
+        }
+    }
+
+    match read_index_json(docs_path) {
+        Ok(index_data) => {
+            for date in changed.keys() {
+                if !index_data
+                    .scores
+                    .iter()
+                    .any(|entry| entry.parsed_date() == Ok(*date))
+                {
+                    log::warn!("{date} has no index.json entry to update; add it to the index");
+                }
+            }
+        }
+        Err(e) => log::warn!(
+            "Could not read index.json: {}",
+            format_error(&e, verbose_errors)
+        ),
+    }
+
+    // A changed file must be recomputed even if its entry looks settled.
+    let options = IndexUpdateOptions {
+        incremental: false,
+        only_dates: Some(changed.keys().copied().collect()),
+        ..index_update_options.clone()
+    };
+    match grq_validation::utils::update_index_with_performance_with_options(docs_path, &options) {
+        Ok(()) => info!(
+            "Updated index.json for {}",
+            changed
+                .keys()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(e) => log::error!(
+            "Failed to update index.json: {}",
+            format_error(&e, verbose_errors)
+        ),
+    }
+}
+
+/// Applies `update` to the `date` entry of `<docs_path>/scores/index.json` and
+/// writes the index back. Entries are matched by parsed date, so an unpadded
+/// `2024-12-3` entry is found for `2024-12-03`. When no entry matches, a
+/// warning is logged and the index is left as it is. An `--as-of` replay
+/// leaves the index untouched: its figures describe a past date, not the live
+/// state of the scores.
+fn update_index_entry(
+    docs_path: &str,
+    date: &str,
+    as_of: Option<NaiveDate>,
+    update: impl FnOnce(&mut ScoreEntry),
+) -> Result<()> {
+    if let Some(as_of) = as_of {
+        info!("Replaying as of {as_of}; leaving index.json unchanged for {date}");
+        return Ok(());
+    }
+    let target = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .with_context(|| format!("Invalid index date: {date}"))?;
+    let mut index_data = read_index_json_in_file_order(docs_path)?;
+    let Some(entry) = index_data
+        .scores
+        .iter_mut()
+        .find(|entry| entry.parsed_date() == Ok(target))
+    else {
+        log::warn!("index.json has no entry for {date}; leaving it unchanged");
+        return Ok(());
+    };
+    update(entry);
+    let index_path = Path::new(docs_path).join("scores").join("index.json");
+    write_index_atomic(index_path, &index_data)?;
+    Ok(())
+}
+
+/// Renders the scores index as an HTML report at `output_path`, when one was
+/// requested with `--html-report`.
+fn write_html_report_if_requested(docs_path: &str, output_path: Option<&str>) -> Result<()> {
+    if let Some(output_path) = output_path {
+        write_html_report(&read_index_json(docs_path)?, output_path)?;
+    }
+    Ok(())
+}
+
+/// Renders `index_data` as an HTML report at `output_path`.
+fn write_html_report(index_data: &IndexData, output_path: &str) -> Result<()> {
+    generate_html_report(index_data, output_path)
+        .with_context(|| format!("writing HTML report to {output_path}"))?;
+    info!("Wrote HTML performance report to {output_path}");
+    Ok(())
+}
+
+/// Renders the portfolio's daily volatility, Sharpe-style ratio and maximum
+/// drawdown, noting any stocks with too few closes to contribute volatility.
+fn format_risk_summary(performance: &PortfolioPerformance, risk_free_rate: f64) -> String {
+    let sharpe = performance
+        .sharpe_ratio(risk_free_rate)
+        .map_or_else(|| "n/a".to_string(), |sharpe| format!("{sharpe:.2}"));
+    let mut summary = format!(
+        "Volatility: {:.2}% daily, Sharpe Ratio: {sharpe} (risk-free {risk_free_rate:.2}%), \
+         Max Drawdown: {:.2}%",
+        performance.volatility_percent, performance.max_drawdown_percent
+    );
+    if !performance.low_data_tickers.is_empty() {
+        summary.push_str(&format!(
+            "; zero volatility for {} (fewer than 2 closes)",
+            performance.low_data_tickers.join(", ")
+        ));
+    }
+    summary
+}
+
+/// Writes the portfolio's 90-day equity curve, resampled to `freq`, to
+/// `output_path`, when one was requested with `--equity-curve`.
+fn write_equity_curve_if_requested(
+    output_path: Option<&str>,
+    freq: Freq,
+    stock_records: &[StockRecord],
+    market: &MarketDataCsv,
+    score_file_date: &str,
+    max_buy_gap_days: u32,
+) -> Result<()> {
+    if let Some(output_path) = output_path {
+        let curve = compute_equity_curve(
+            stock_records,
+            &market.closes,
+            &market.points,
+            score_file_date,
+            90,
+            max_buy_gap_days,
+        )?;
+        let curve: Vec<(NaiveDate, f64)> = resample(&curve.into_iter().collect(), freq)
+            .into_iter()
+            .collect();
+        write_equity_curve_csv(output_path, &curve)
+            .with_context(|| format!("writing equity curve to {output_path}"))?;
+        info!("Wrote {}-point equity curve to {output_path}", curve.len());
+    }
+    Ok(())
+}
+
+/// Writes the score file's picks to `output_path` as a broker import CSV,
+/// when one was requested with `--paper-trade`.
+fn write_paper_trades_if_requested(
+    output_path: Option<&str>,
+    capital: Option<f64>,
+    stock_records: &[StockRecord],
+    market_data_csv: &HashMap<String, BTreeMap<NaiveDate, f64>>,
+    score_file_date: &str,
+    max_buy_gap_days: u32,
+) -> Result<()> {
+    if let Some(output_path) = output_path {
+        let trades = paper_trades(
+            stock_records,
+            market_data_csv,
+            score_file_date,
+            capital,
+            max_buy_gap_days,
+        )?;
+        write_paper_trade_csv(output_path, &trades)
+            .with_context(|| format!("writing paper trades to {output_path}"))?;
+        info!("Wrote {} paper trades to {output_path}", trades.len());
+    }
+    Ok(())
+}
+
+/// Formats the all-time summary of completed score files.
+fn format_overall_summary(summary: &OverallSummary) -> String {
+    let percent =
+        |value: Option<f64>| value.map_or_else(|| "n/a".to_string(), |v| format!("{v:.2}%"));
+    format!(
+        "All-Time Summary: {} completed dates, Mean Annualized: {}, Median Annualized: {}, Best: {}, Worst: {}",
+        summary.total_dates,
+        percent(summary.mean_annualized),
+        percent(summary.median_annualized),
+        summary.best_date.as_deref().unwrap_or("n/a"),
+        summary.worst_date.as_deref().unwrap_or("n/a")
+    )
+}
+
+/// Formats the mean realised return per score bucket, highest bucket first.
+fn format_score_bucket_returns(returns: &BTreeMap<ScoreBucket, f64>) -> String {
+    if returns.is_empty() {
+        return "n/a".to_string();
+    }
+    returns
+        .iter()
+        .rev()
+        .map(|(bucket, mean_return)| format!("{bucket}: {mean_return:.2}%"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Prints the headline figures of a settled `performance` for `date`, whose
+/// score file lists `stock_records` and whose headline return is of
+/// `return_type`.
+fn print_performance_results(
+    date: &str,
+    performance: &PortfolioPerformance,
+    stock_records: &[StockRecord],
+    return_type: ReturnType,
+    risk_free_rate: f64,
+) {
+    println!("\n=== {date} Performance Results ({return_type}) ===");
+    println!("Score Date: {}", performance.score_date);
+    println!("Total Stocks: {} (included)", performance.total_stocks);
+    if !performance.excluded_tickers.is_empty() {
+        println!("Excluded Stocks: {}", performance.excluded_tickers.len());
+        for ticker in &performance.excluded_tickers {
+            println!("  - {ticker} (unpriceable)");
+        }
+    }
+    println!("90-Day Performance: {:.2}%", performance.performance_90_day);
+    println!(
+        "Annualized Performance: {:.2}% ({})",
+        performance.performance_annualized,
+        performance.annualization_label()
+    );
+    println!("{}", performance.winners_losers_summary());
+    println!(
+        "Score/Return Correlation: {}",
+        format_correlation(score_return_correlation(
+            &performance.individual_performances
+        ))
+    );
+    println!(
+        "Return by Score Bucket: {}",
+        format_score_bucket_returns(&score_bucket_returns(
+            stock_records,
+            &performance.individual_performances
+        ))
+    );
+    println!("{}", format_risk_summary(performance, risk_free_rate));
+}
+
+/// Prints one line per stock of a settled `performance`, with its dividends.
+fn print_stock_performances(performance: &PortfolioPerformance) {
+    println!("Individual Stock Performances:");
+    for stock_perf in &performance.individual_performances {
+        let reinvestment = stock_perf
+            .reinvestment_delta_percent
+            .map(|delta| format!(" (reinvested, {delta:+.2}% vs cash)"))
+            .unwrap_or_default();
+        println!("  {}: Buy=${:.2}, Current=${:.2}, Gain/Loss={:.2}%, Dividends=${:.2}, Total Return={:.2}%, To Target={}, IRR={}, Volatility={:.2}%, Intrinsic Upside={}, Data={} closes over {} days{}",
+            stock_perf.ticker,
+            stock_perf.buy_price,
+            stock_perf.current_price,
+            stock_perf.gain_loss_percent,
+            stock_perf.dividends_total,
+            stock_perf.total_return_percent,
+            format_progress_to_target(stock_perf.progress_to_target_percent),
+            stock_perf
+                .irr_percent
+                .map_or_else(|| "n/a".to_string(), |irr| format!("{irr:.2}%")),
+            stock_perf.volatility_percent,
+            format_intrinsic_upside(stock_perf.intrinsic_upside_percent),
+            stock_perf.data_points,
+            stock_perf.coverage_days,
+            reinvestment
+        );
+        if !stock_perf.dividends.is_empty() {
+            println!(
+                "    Dividends: {}",
+                format_dividend_items(&stock_perf.dividends)
+            );
+        }
+    }
+}
+
+/// Prints the stocks that met their target under `when`, with their count.
+fn print_target_winners(performance: &PortfolioPerformance, when: TargetMet) {
+    let winners = stocks_meeting_target(&performance.individual_performances, when);
+    let condition = match when {
+        TargetMet::AtWindowEnd => "by the end of the window",
+        TargetMet::AnyTime => "at any point in the window",
+    };
+    println!(
+        "Stocks that met their target {condition}: {} of {}",
+        winners.len(),
+        performance.individual_performances.len()
+    );
+    for stock in winners {
+        let first_hit = stock
+            .target_hit_date
+            .map(|date| format!(", first hit {date}"))
+            .unwrap_or_default();
+        println!(
+            "  {}: Target=${:.2}, Current=${:.2}, Total Return={:.2}%{first_hit}",
+            stock.ticker, stock.target_price, stock.current_price, stock.total_return_percent
+        );
+    }
+}
+
+/// Prints the headline figures of a hybrid projection for `date`.
+fn print_projection_results(date: &str, performance: &PortfolioPerformance, risk_free_rate: f64) {
+    println!("\n=== {date} Projection Results ===");
+    println!("Score Date: {}", performance.score_date);
+    println!("Total Stocks: {} (included)", performance.total_stocks);
+    if !performance.excluded_tickers.is_empty() {
+        println!("Excluded Stocks: {}", performance.excluded_tickers.len());
+        for ticker in &performance.excluded_tickers {
+            println!("  - {ticker} (unpriceable)");
+        }
+    }
+    println!(
+        "Projected 90-Day Performance: {:.2}%",
+        performance.performance_90_day
+    );
+    println!(
+        "Projected Annualized Performance: {:.2}%",
+        performance.performance_annualized
+    );
+    println!("{}", performance.winners_losers_summary());
+    println!(
+        "Score/Return Correlation: {}",
+        format_correlation(score_return_correlation(
+            &performance.individual_performances
+        ))
+    );
+    println!("{}", format_risk_summary(performance, risk_free_rate));
+}
+
+/// Prints one line per stock of a hybrid projection, with its dividends.
+fn print_stock_projections(performance: &PortfolioPerformance) {
+    println!("Individual Stock Projections:");
+    for stock_perf in &performance.individual_performances {
+        println!("  {}: Buy=${:.2}, Current=${:.2}, Projected Gain/Loss={:.2}%, Dividends=${:.2}, Total Return={:.2}%, To Target={}, Volatility={:.2}%, Intrinsic Upside={}, Data={} closes over {} days",
+            stock_perf.ticker,
+            stock_perf.buy_price,
+            stock_perf.current_price,
+            stock_perf.gain_loss_percent,
+            stock_perf.dividends_total,
+            stock_perf.total_return_percent,
+            format_progress_to_target(stock_perf.progress_to_target_percent),
+            stock_perf.volatility_percent,
+            format_intrinsic_upside(stock_perf.intrinsic_upside_percent),
+            stock_perf.data_points,
+            stock_perf.coverage_days
+        );
+        if !stock_perf.dividends.is_empty() {
+            println!(
+                "    Dividends: {}",
+                format_dividend_items(&stock_perf.dividends)
+            );
+        }
+    }
+}
+
+/// Renders an error for a log line: its message, or with `verbose` every
+/// cause in its source chain as `outer: cause: root`.
+fn format_error(error: &(dyn std::error::Error + 'static), verbose: bool) -> String {
+    if !verbose {
+        return error.to_string();
+    }
+    std::iter::successors(Some(error), |error| error.source())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+/// Renders a score/return correlation, or `n/a` when it is undefined.
+fn format_correlation(correlation: Option<f64>) -> String {
+    correlation.map_or_else(|| "n/a".to_string(), |r| format!("{r:.3}"))
+}
+
+/// Renders an intrinsic-value upside for the per-stock output, or `n/a` when
+/// the score file carried no intrinsic value.
+fn format_intrinsic_upside(upside_percent: Option<f64>) -> String {
+    upside_percent.map_or_else(|| "n/a".to_string(), |upside| format!("{upside:+.2}%"))
+}
+
+/// Renders progress to target for the per-stock output, or `n/a` when the
+/// score file gave no target.
+fn format_progress_to_target(progress_percent: Option<f64>) -> String {
+    progress_percent.map_or_else(|| "n/a".to_string(), |progress| format!("{progress:.1}%"))
+}
+
+/// Renders a stock's dividends as `2025-05-15: $0.09, 2025-08-15: $0.09`.
+fn format_dividend_items(dividends: &[(NaiveDate, f64)]) -> String {
+    dividends
+        .iter()
+        .map(|(ex_date, amount)| format!("{ex_date}: ${amount:.2}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Checks a generated `kind` CSV for `--verify`, logging each problem found.
+/// Returns whether the CSV is well-formed.
+fn verify_csv(path: &str, kind: CsvKind) -> bool {
+    let issues = verify_generated_csv(path, kind);
+    for issue in &issues {
+        log::warn!("{issue}");
+    }
+    if !issues.is_empty() {
+        log::error!("{} problems in generated CSV {path}", issues.len());
+    }
+    issues.is_empty()
+}
+
+/// Logs every missing-data problem and fails when there are any, for
+/// `--fail-on-missing-data`.
+fn fail_on_missing_data(problems: &[String]) -> Result<()> {
+    if problems.is_empty() {
+        return Ok(());
+    }
+    for problem in problems {
+        log::error!("Missing data: {problem}");
+    }
+    Err(anyhow!(
+        "{} stocks or score files have missing data (--fail-on-missing-data)",
+        problems.len()
+    ))
+}
+
+/// Counters gathered over one batch run for the closing summary line.
+#[derive(Default)]
+struct RunStats {
+    /// Score files the run attempted.
+    processed: usize,
+    /// Score files whose performance was calculated with every stock priced.
+    full_data: usize,
+    /// Score files skipped for an unsafe path, a missing file, no stock
+    /// records or fewer priced stocks than `--min-stocks`.
+    skipped: usize,
+    /// Score files whose tickers or performance could not be read.
+    errors: usize,
+    /// Annualised performance of each score file that was calculated.
+    annualized: Vec<f64>,
+    /// Time spent writing index.json, or `None` for an --as-of replay, which
+    /// leaves it unchanged.
+    index_write_time: Option<Duration>,
+}
+
+impl RunStats {
+    /// Formats the one-line run summary, given the run's total duration.
+    fn summary(&self, elapsed: Duration) -> String {
+        let mean_annualized = if self.annualized.is_empty() {
+            "n/a".to_string()
+        } else {
+            format!(
+                "{:.1}%",
+                self.annualized.iter().sum::<f64>() / self.annualized.len() as f64
+            )
+        };
+        let index_write = match self.index_write_time {
+            Some(time) => format!("wrote index.json in {:.1}s", time.as_secs_f64()),
+            None => "left index.json unchanged".to_string(),
+        };
+        format!(
+            "Processed {} score files in {:.1}s: {} with full data, {} skipped, {} errors, mean annualized {mean_annualized}, {index_write}",
+            self.processed,
+            elapsed.as_secs_f64(),
+            self.full_data,
+            self.skipped,
+            self.errors,
+        )
+    }
+}
+
+/// Returns the missing-data problems for one score file as display lines,
+/// treating a file that cannot be checked as a problem itself.
+fn missing_data_problems(
+    score_file_path: &str,
+    score_file_date: &str,
+    max_buy_gap_days: u32,
+    data: &DataOptions,
+) -> Vec<String> {
+    match find_missing_data(score_file_path, score_file_date, max_buy_gap_days, data) {
+        Ok(missing) => missing.iter().map(ToString::to_string).collect(),
+        Err(e) => vec![format!("{score_file_path}: {e}")],
+    }
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Initialize logging
-    if args.verbose {
-        env_logger::init_from_env(env_logger::Env::default().default_filter_or("debug"));
+    // Initialize logging. With --progress the logger is routed through the
+    // progress bars so log lines print above the bar instead of through it.
+    let default_filter = if args.verbose {
+        "debug"
+    } else if args.quiet {
+        "warn"
+    } else {
+        "info"
+    };
+    let mut builder =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(default_filter));
+    if args.log_format == LogFormat::Json {
+        builder.format(|buf, record| {
+            let line = serde_json::json!({
+                "timestamp": buf.timestamp().to_string(),
+                "level": record.level().as_str(),
+                "target": record.target(),
+                "message": record.args().to_string(),
+            });
+            writeln!(buf, "{line}")
+        });
+    }
+    let logger = builder.build();
+    let max_level = logger.filter();
+    let multi_progress = MultiProgress::new();
+    if args.progress {
+        LogWrapper::new(multi_progress.clone(), logger).try_init()?;
     } else {
-        env_logger::init_from_env(env_logger::Env::default().default_filter_or("info"));
+        log::set_boxed_logger(Box::new(logger))?;
+    }
+    log::set_max_level(max_level);
+
+    if let (Some(since), Some(until)) = (args.since, args.until) {
+        if since > until {
+            return Err(anyhow!(
+                "--since {since} is after --until {until}; the range would be empty"
+            ));
+        }
+    }
+
+    if let Some(limit) = args.max_concurrent_reads {
+        set_max_concurrent_reads(limit);
+    }
+    let mut data = DataOptions {
+        market_data_paths: args.market_data_path.iter().map(PathBuf::from).collect(),
+        dividend_data_path: args.dividend_data_path.as_ref().map(PathBuf::from),
+        cache_dir: args
+            .cache_dir
+            .clone()
+            .or_else(|| {
+                std::env::var_os(MARKET_DATA_CACHE_ENV)
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from)
+            })
+            .filter(|_| !args.no_cache),
+        artifacts_dir: args.artifacts_dir.clone(),
+        strict: args.strict,
+        ..DataOptions::default()
+    };
+    if let Some(path) = &args.market_data_csv {
+        data.load_market_data_csv(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+    }
+    if let Some(path) = &args.symbol_aliases {
+        data.symbol_aliases = read_symbol_aliases(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
     }
 
     info!("Starting GRQ Validation processor");
     info!("Docs path: {}", args.docs_path);
 
+    let performance_options = PerformanceOptions {
+        reinvest_dividends: args.reinvest,
+        price_divisors: args.price_divisors.into_iter().collect(),
+        max_buy_gap_days: args.max_buy_gap_days,
+        allow_duplicates: args.allow_duplicates,
+        entry_price: args.entry_price,
+        dividend_fx_rates: args.dividend_fx_rates.into_iter().collect(),
+        annualization_basis: args.annualization_basis,
+        trading_calendar: TradingCalendar::with_holidays(args.holidays.iter().copied()),
+        min_stocks: args.min_stocks,
+        end_price_policy: args.end_price,
+        return_type: args.return_type,
+        shared_dividends: None,
+        data,
+    };
+    let data = &performance_options.data;
+
+    let projection_options = ProjectionOptions {
+        dampening: args.projection_dampening,
+        dampening_min: args.projection_dampening_min,
+        as_of: args.as_of,
+        trading_calendar: (args.trading_days || !args.holidays.is_empty())
+            .then(|| TradingCalendar::with_holidays(args.holidays.iter().copied())),
+        clock: std::sync::Arc::new(SystemClock),
+    };
+
+    // An --as-of replay must not overwrite the live index.json or the CSVs
+    // and detail JSON beside the score files; it only writes them when they
+    // go to a separate --artifacts-dir.
+    let write_artifacts = args.as_of.is_none() || args.artifacts_dir.is_some();
+
+    if args.validate_only {
+        let score_files = find_score_files(&args.docs_path)?;
+        let mut failed_files = 0;
+        let mut problem_count = 0;
+        let mut warning_count = 0;
+        // Files are parsed in parallel but reported in path order.
+        for (issues, warnings) in validate_score_files(&score_files, args.max_intrinsic_ratio) {
+            if !issues.is_empty() {
+                failed_files += 1;
+                problem_count += issues.len();
+                for issue in &issues {
+                    println!("{issue}");
+                }
+            }
+            for warning in &warnings {
+                log::warn!("{warning}");
+                warning_count += 1;
+            }
+        }
+
+        println!(
+            "Validated {} score files: {failed_files} with problems ({problem_count} total), \
+             {warning_count} intrinsic-value warnings",
+            score_files.len()
+        );
+        if failed_files > 0 {
+            return Err(anyhow!("{failed_files} score files failed validation"));
+        }
+        return Ok(());
+    }
+
+    if args.rebuild_index {
+        let index_data = rebuild_index(&args.docs_path).context("rebuilding index.json")?;
+        let with_performance = index_data
+            .scores
+            .iter()
+            .filter(|entry| entry.performance_90_day.is_some())
+            .count();
+        let index_path = Path::new(&args.docs_path).join("scores").join("index.json");
+        write_index_atomic(index_path, &index_data)?;
+        println!(
+            "Rebuilt index.json with {} score files ({with_performance} with performance figures)",
+            index_data.scores.len()
+        );
+        return Ok(());
+    }
+
+    if args.summary {
+        let index_data = match &args.index_url {
+            Some(url) => {
+                read_index_json_from_url(url).with_context(|| format!("summarising {url}"))?
+            }
+            None => read_index_json(&args.docs_path).context("summarising index.json")?,
+        };
+        let summary = overall_summary_as_of(&index_data, projection_options.reference_date());
+        println!("{}", format_overall_summary(&summary));
+        return Ok(());
+    }
+
+    if args.projection_accuracy {
+        let accuracy = projection_accuracy(
+            &args.docs_path,
+            DEFAULT_ACCURACY_ELAPSED_DAYS,
+            &projection_options,
+            data,
+        )
+        .context("measuring projection accuracy")?;
+
+        println!("\n=== Projection Accuracy vs Realised 90-Day Return ===");
+        println!("{:>8} {:>8} {:>10} {:>10}", "Day", "Samples", "MAE", "Bias");
+        for bucket in &accuracy {
+            println!(
+                "{:>8} {:>8} {:>9.2}% {:>+9.2}%",
+                bucket.elapsed_days, bucket.samples, bucket.mean_absolute_error, bucket.bias
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(old_index) = &args.diff {
+        let index_path = match &args.index_url {
+            Some(url) => PathBuf::from(url),
+            None => Path::new(&args.docs_path).join("scores").join("index.json"),
+        };
+        let changes = diff_index(old_index, &index_path, args.diff_threshold)
+            .with_context(|| format!("comparing {old_index} with {}", index_path.display()))?;
+
+        let figure =
+            |value: Option<f64>| value.map_or_else(|| "n/a".to_string(), |v| format!("{v:.2}%"));
+        println!(
+            "\n=== Index Changes (threshold {:.2} pts) ===",
+            args.diff_threshold
+        );
+        println!("{:<12} {:>24} {:>24}", "Date", "90-Day", "Annualized");
+        for change in &changes {
+            println!(
+                "{:<12} {:>24} {:>24}",
+                change.date,
+                format!(
+                    "{} -> {}",
+                    figure(change.old_performance_90_day),
+                    figure(change.new_performance_90_day)
+                ),
+                format!(
+                    "{} -> {}",
+                    figure(change.old_performance_annualized),
+                    figure(change.new_performance_annualized)
+                )
+            );
+        }
+        println!("{} dates changed", changes.len());
+        return Ok(());
+    }
+
+    // A published index can be analysed but never written back, so past the
+    // read-only modes above it is only good for an HTML report.
+    if let Some(url) = &args.index_url {
+        let output_path = args.html_report.as_deref().ok_or_else(|| {
+            anyhow!("--index-url is read-only: use it with --summary, --diff or --html-report")
+        })?;
+        let index_data = read_index_json_from_url(url).with_context(|| format!("reading {url}"))?;
+        return write_html_report(&index_data, output_path);
+    }
+
+    if let Some(symbol) = &args.inspect_ticker {
+        let market_data = read_market_data_with_options(symbol, data)
+            .with_context(|| format!("reading market data for {symbol}"))?;
+        let calendar = TradingCalendar::with_holidays(args.holidays.iter().copied());
+        let coverage =
+            market_data_coverage(&market_data, &calendar, DEFAULT_MAX_DATA_GAP_TRADING_DAYS)
+                .ok_or_else(|| anyhow!("market data for {symbol} has no dated entries"))?;
+
+        println!("\n=== {symbol} Market Data ===");
+        println!("First Date: {}", coverage.first_date);
+        println!("Last Date: {}", coverage.last_date);
+        println!("Data Points: {}", coverage.data_points);
+        println!(
+            "Gaps over {DEFAULT_MAX_DATA_GAP_TRADING_DAYS} trading days: {}",
+            coverage.gaps.len()
+        );
+        for gap in &coverage.gaps {
+            println!(
+                "  {} -> {} ({} trading days missing)",
+                gap.after, gap.before, gap.missing_trading_days
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(ticker) = &args.ticker {
+        let appearances = calculate_ticker_history(&args.docs_path, ticker, &performance_options)
+            .with_context(|| format!("tracking {ticker}"))?;
+
+        println!("\n=== {ticker} Across Score Files ===");
+        for appearance in &appearances {
+            let stock_perf = &appearance.performance;
+            println!(
+                "  {} ({}): Buy=${:.2}, Current=${:.2}, Total Return={:.2}%",
+                appearance.score_date,
+                stock_perf.ticker,
+                stock_perf.buy_price,
+                stock_perf.current_price,
+                stock_perf.total_return_percent
+            );
+        }
+        let summary = summarize_ticker_history(&appearances);
+        let percent =
+            |value: Option<f64>| value.map_or_else(|| "n/a".to_string(), |v| format!("{v:.2}%"));
+        println!(
+            "Appearances: {}, Mean Return: {}, Win Rate: {}",
+            summary.appearances,
+            percent(summary.mean_return),
+            percent(summary.win_rate)
+        );
+        return Ok(());
+    }
+
+    // Score a TSV piped in on stdin
+    if let Some(score_date) = args.score_date {
+        ensure_market_data_repository_with_options(data)?;
+        let date = score_date.format("%Y-%m-%d").to_string();
+        let stock_records = read_tsv_score_reader_checked(
+            std::io::stdin().lock(),
+            "<stdin>",
+            args.allow_duplicates,
+        )
+        .context("reading score TSV from stdin")?;
+        let tickers: Vec<String> = stock_records
+            .iter()
+            .map(|record| record.stock.clone())
+            .collect();
+        let market = market_data_for_tickers(&tickers, &date, data)
+            .with_context(|| format!("reading market data from {date}"))?;
+
+        if (projection_options.reference_date() - score_date).num_days() >= 90 {
+            let performance = calculate_portfolio_performance_for_records(
+                &stock_records,
+                &market,
+                &date,
+                &performance_options,
+            )
+            .with_context(|| format!("calculating performance for {date}"))?;
+            print_performance_results(
+                &date,
+                &performance,
+                &stock_records,
+                args.return_type,
+                args.risk_free_rate,
+            );
+            println!();
+            match args.only_winners_vs_target {
+                Some(when) => print_target_winners(&performance, when),
+                None => print_stock_performances(&performance),
+            }
+        } else {
+            let performance = grq_validation::utils::calculate_hybrid_projection_with_options(
+                &stock_records,
+                &date,
+                &market.closes,
+                &projection_options,
+                &performance_options,
+            )
+            .with_context(|| format!("calculating projection for {date}"))?;
+            print_projection_results(&date, &performance, args.risk_free_rate);
+            println!();
+            match args.only_winners_vs_target {
+                Some(when) => print_target_winners(&performance, when),
+                None => print_stock_projections(&performance),
+            }
+        }
+        return Ok(());
+    }
+
     // Process a specific date if provided
     if let Some(date) = args.date {
         info!("Processing specific date: {date}");
@@ -65,180 +1390,284 @@ fn main() -> Result<()> {
         let month = date_parts[1];
         let day = date_parts[2];
 
-        // Convert month number to month name
-        let month_name = match month {
-            "01" => "January",
-            "02" => "February",
-            "03" => "March",
-            "04" => "April",
-            "05" => "May",
-            "06" => "June",
-            "07" => "July",
-            "08" => "August",
-            "09" => "September",
-            "10" => "October",
-            "11" => "November",
-            "12" => "December",
-            _ => return Err(anyhow!("Invalid month: {month}")),
-        };
+        // Resolve the month (a number, or a full or abbreviated name in any
+        // case) and restate the date with a numeric month so the rest of the
+        // run sees YYYY-MM-DD.
+        let month_number = month
+            .parse::<u32>()
+            .ok()
+            .or_else(|| month_number(month))
+            .filter(|&number| month_name(number).is_some())
+            .ok_or_else(|| {
+                anyhow!("Invalid month: {month} (expected 01-12 or a month name such as Jun)")
+            })?;
+        let date = format!("{year}-{month_number:02}-{day}");
+        let parsed_date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+            .with_context(|| format!("Invalid date: {date}"))?;
+        let date = parsed_date.format("%Y-%m-%d").to_string();
 
-        let score_file_path = format!(
-            "{}/scores/{}/{}/{}.tsv",
-            args.docs_path, year, month_name, day
-        );
+        // Newer archives name the file after the ISO date; older ones use
+        // month-name folders.
+        let score_file_path = score_file_path_for_date(&args.docs_path, parsed_date);
         let score_file_date = &date;
+        if args.fail_on_missing_data {
+            fail_on_missing_data(&missing_data_problems(
+                &score_file_path,
+                score_file_date,
+                args.max_buy_gap_days,
+                data,
+            ))?;
+        }
 
         // Check if the date is less than 90 days old
         let score_date = NaiveDate::parse_from_str(score_file_date, "%Y-%m-%d")?;
-        let current_date = Utc::now().naive_utc().date();
+        let current_date = projection_options.reference_date();
         let days_since_score = (current_date - score_date).num_days();
 
         if days_since_score >= 90 {
             // Use regular performance calculation. `?` propagates the error to
             // `main`, which prints the full context chain on exit.
-            let performance = grq_validation::utils::calculate_portfolio_performance(
+            let mut performance =
+                grq_validation::utils::calculate_portfolio_performance_with_options(
+                    &score_file_path,
+                    score_file_date,
+                    &performance_options,
+                )
+                .with_context(|| format!("calculating performance for {date}"))?;
+
+            let stock_records = grq_validation::utils::read_tsv_score_file_checked(
                 &score_file_path,
+                args.allow_duplicates,
+            )
+            .with_context(|| format!("reading TSV file {score_file_path}"))?;
+            print_performance_results(
+                &date,
+                &performance,
+                &stock_records,
+                args.return_type,
+                args.risk_free_rate,
+            );
+            let market = read_market_data_from_csv_with_options(
+                &derive_csv_output_path_with_options(&score_file_path, CsvKind::Market, data),
+                data,
+            )
+            .context("reading market data CSV")?;
+            let horizons = calculate_horizon_returns_for_records(
+                &stock_records,
+                &market,
                 score_file_date,
+                &performance_options,
             )
-            .with_context(|| format!("calculating performance for {date}"))?;
-
-            println!("\n=== {date} Performance Results ===");
-            println!("Score Date: {}", performance.score_date);
-            println!("Total Stocks: {} (included)", performance.total_stocks);
-            if !performance.excluded_tickers.is_empty() {
-                println!("Excluded Stocks: {}", performance.excluded_tickers.len());
-                for ticker in &performance.excluded_tickers {
-                    println!("  - {ticker} (unpriceable)");
-                }
-            }
-            println!("90-Day Performance: {:.2}%", performance.performance_90_day);
+            .with_context(|| format!("calculating horizon returns for {date}"))?;
             println!(
-                "Annualized Performance: {:.2}%",
-                performance.performance_annualized
+                "Horizon Returns: 30d={:.2}%, 60d={:.2}%, 90d={:.2}%",
+                horizons.return_30d, horizons.return_60d, horizons.return_90d
             );
+            let dollar_value = calculate_dollar_portfolio_value(
+                &stock_records,
+                &performance.individual_performances,
+            );
+            write_equity_curve_if_requested(
+                args.equity_curve.as_deref(),
+                args.resample,
+                &stock_records,
+                &market,
+                score_file_date,
+                args.max_buy_gap_days,
+            )?;
+            write_paper_trades_if_requested(
+                args.paper_trade.as_deref(),
+                args.capital,
+                &stock_records,
+                &market.closes,
+                score_file_date,
+                args.max_buy_gap_days,
+            )?;
+            if dollar_value.positions > 0 {
+                println!(
+                    "Dollar P&L: ${:+.2} ({:+.2}%) on ${:.2} across {} sized positions",
+                    dollar_value.pnl(),
+                    dollar_value.pnl_percent(),
+                    dollar_value.start_value,
+                    dollar_value.positions
+                );
+            }
+            if let Some(total_capital) = args.total_capital {
+                print_blended_return(&dollar_value, total_capital);
+            }
+            if !args.benchmark.is_empty() {
+                // Measure the benchmarks over the days the portfolio's own
+                // figures cover, so returns and alpha compare like with like.
+                let end_date = (score_date
+                    + chrono::Duration::days(performance.actual_days_elapsed))
+                .format("%Y-%m-%d")
+                .to_string();
+                let mut benchmark_returns = Vec::new();
+                for symbol in &args.benchmark {
+                    match calculate_benchmark_return(symbol, score_file_date, &end_date, data) {
+                        Ok(return_percent) => {
+                            benchmark_returns.push((symbol.clone(), return_percent));
+                        }
+                        Err(e) => log::warn!(
+                            "Skipping benchmark {symbol}: {}",
+                            format_error(&e, args.verbose_errors)
+                        ),
+                    }
+                }
+                let comparison =
+                    rank_against_benchmarks(performance.performance_90_day, &benchmark_returns);
+                println!("Benchmarks ({score_file_date} to {end_date}):");
+                for benchmark in &comparison.benchmarks {
+                    let marker = if benchmark.beaten {
+                        "beaten"
+                    } else {
+                        "not beaten"
+                    };
+                    println!(
+                        "  {}: {:.2}% ({marker})",
+                        benchmark.symbol, benchmark.return_percent
+                    );
+                }
+                println!(
+                    "Portfolio rank: {} of {} ({:.0}th percentile)",
+                    comparison.rank,
+                    comparison.benchmarks.len() + 1,
+                    comparison.percentile
+                );
+                // Alpha is measured against the first benchmark that could be
+                // priced.
+                if let Some((symbol, benchmark_return)) = benchmark_returns.first() {
+                    let alpha = annualized_alpha(&performance, *benchmark_return);
+                    performance.alpha_annualized = Some(alpha);
+                    println!("Alpha (annualized): {alpha:+.2}% vs {symbol}");
+                }
+            }
             println!();
 
-            println!("Individual Stock Performances:");
-            for stock_perf in &performance.individual_performances {
-                println!("  {}: Buy=${:.2}, Current=${:.2}, Gain/Loss={:.2}%, Dividends=${:.2}, Total Return={:.2}%",
-                    stock_perf.ticker,
-                    stock_perf.buy_price,
-                    stock_perf.current_price,
-                    stock_perf.gain_loss_percent,
-                    stock_perf.dividends_total,
-                    stock_perf.total_return_percent
-                );
+            match args.only_winners_vs_target {
+                Some(when) => print_target_winners(&performance, when),
+                None => print_stock_performances(&performance),
             }
 
-            // Update the index.json with this performance data
-            let mut index_data = grq_validation::utils::read_index_json(&args.docs_path)?;
-            for score_entry in &mut index_data.scores {
-                if score_entry.date == date {
-                    score_entry.performance_90_day = Some(performance.performance_90_day);
-                    score_entry.performance_annualized = Some(performance.performance_annualized);
-                    score_entry.total_stocks = Some(performance.total_stocks);
-                    break;
-                }
+            if args.write_detail && write_artifacts {
+                let detail_path = write_performance_detail(&score_file_path, &performance, data)?;
+                info!("Wrote performance detail to {detail_path}");
             }
 
-            // Write updated index back to file
-            let index_path = Path::new(&args.docs_path).join("scores").join("index.json");
-            let json_content = serde_json::to_string_pretty(&index_data)?;
-            std::fs::write(index_path, json_content)?;
-            println!("\nUpdated index.json with performance data for {date}");
+            // Update the index.json with this performance data
+            update_index_entry(&args.docs_path, &date, args.as_of, |score_entry| {
+                score_entry.performance_90_day = Some(performance.performance_90_day);
+                score_entry.performance_annualized = Some(performance.performance_annualized);
+                score_entry.total_stocks = Some(performance.total_stocks);
+                score_entry.set_horizon_returns(&horizons);
+            })?;
         } else {
             // Use hybrid projection for dates less than 90 days old. Each step
             // propagates with `?` plus context instead of a nested match ladder.
-            let stock_records = grq_validation::utils::read_tsv_score_file(&score_file_path)
-                .with_context(|| format!("reading TSV file {score_file_path}"))?;
-            let market_data_csv = grq_validation::utils::read_market_data_from_csv(
-                &grq_validation::utils::derive_csv_output_path(&score_file_path),
+            let stock_records = grq_validation::utils::read_tsv_score_file_checked(
+                &score_file_path,
+                args.allow_duplicates,
+            )
+            .with_context(|| format!("reading TSV file {score_file_path}"))?;
+            let market = read_market_data_from_csv_with_options(
+                &derive_csv_output_path_with_options(&score_file_path, CsvKind::Market, data),
+                data,
             )
-            .context("reading market data CSV")?
-            .closes;
-            let performance = grq_validation::utils::calculate_hybrid_projection(
+            .context("reading market data CSV")?;
+            let performance = grq_validation::utils::calculate_hybrid_projection_with_options(
                 &stock_records,
                 score_file_date,
-                &market_data_csv,
+                &market.closes,
+                &projection_options,
+                &performance_options,
             )
             .with_context(|| format!("calculating projection for {date}"))?;
+            write_equity_curve_if_requested(
+                args.equity_curve.as_deref(),
+                args.resample,
+                &stock_records,
+                &market,
+                score_file_date,
+                args.max_buy_gap_days,
+            )?;
+            write_paper_trades_if_requested(
+                args.paper_trade.as_deref(),
+                args.capital,
+                &stock_records,
+                &market.closes,
+                score_file_date,
+                args.max_buy_gap_days,
+            )?;
 
-            println!("\n=== {date} Projection Results ===");
-            println!("Score Date: {}", performance.score_date);
-            println!("Total Stocks: {} (included)", performance.total_stocks);
-            if !performance.excluded_tickers.is_empty() {
-                println!("Excluded Stocks: {}", performance.excluded_tickers.len());
-                for ticker in &performance.excluded_tickers {
-                    println!("  - {ticker} (unpriceable)");
-                }
-            }
-            println!(
-                "Projected 90-Day Performance: {:.2}%",
-                performance.performance_90_day
-            );
-            println!(
-                "Projected Annualized Performance: {:.2}%",
-                performance.performance_annualized
-            );
+            print_projection_results(&date, &performance, args.risk_free_rate);
             println!();
 
-            println!("Individual Stock Projections:");
-            for stock_perf in &performance.individual_performances {
-                println!("  {}: Buy=${:.2}, Current=${:.2}, Projected Gain/Loss={:.2}%, Dividends=${:.2}, Total Return={:.2}%",
-                    stock_perf.ticker,
-                    stock_perf.buy_price,
-                    stock_perf.current_price,
-                    stock_perf.gain_loss_percent,
-                    stock_perf.dividends_total,
-                    stock_perf.total_return_percent
-                );
+            match args.only_winners_vs_target {
+                Some(when) => print_target_winners(&performance, when),
+                None => print_stock_projections(&performance),
             }
 
-            // Update the index.json with this projection data
-            let mut index_data = grq_validation::utils::read_index_json(&args.docs_path)?;
-            for score_entry in &mut index_data.scores {
-                if score_entry.date == date {
-                    score_entry.performance_90_day = Some(performance.performance_90_day);
-                    score_entry.performance_annualized = Some(performance.performance_annualized);
-                    score_entry.total_stocks = Some(performance.total_stocks);
-                    break;
-                }
+            if args.write_detail && write_artifacts {
+                let detail_path = write_performance_detail(&score_file_path, &performance, data)?;
+                info!("Wrote performance detail to {detail_path}");
             }
 
-            // Write updated index back to file
-            let index_path = Path::new(&args.docs_path).join("scores").join("index.json");
-            let json_content = serde_json::to_string_pretty(&index_data)?;
-            std::fs::write(index_path, json_content)?;
-            println!("\nUpdated index.json with projection data for {date}");
+            // Update the index.json with this projection data
+            update_index_entry(&args.docs_path, &date, args.as_of, |score_entry| {
+                score_entry.performance_90_day = Some(performance.performance_90_day);
+                score_entry.performance_annualized = Some(performance.performance_annualized);
+                score_entry.total_stocks = Some(performance.total_stocks);
+                score_entry.clear_horizon_returns();
+            })?;
         }
 
         info!("Single date processing completed");
+        write_html_report_if_requested(&args.docs_path, args.html_report.as_deref())?;
         return Ok(());
     }
 
+    let index_update_options = IndexUpdateOptions {
+        incremental: args.incremental,
+        projection: projection_options.clone(),
+        performance: performance_options.clone(),
+        error_policy: args.error_policy,
+        only_dates: None,
+    };
+
     // Calculate performance for all score files that are at least 90 days old
     if args.calculate_performance {
         info!("Calculating performance metrics for all score files...");
-        match grq_validation::utils::update_index_with_performance(&args.docs_path) {
+        match grq_validation::utils::update_index_with_performance_with_options(
+            &args.docs_path,
+            &index_update_options,
+        ) {
             Ok(_) => {
                 info!("Successfully updated index.json with performance metrics");
             }
             Err(e) => {
-                log::error!("Failed to update performance metrics: {e}");
+                log::error!(
+                    "Failed to update performance metrics: {}",
+                    format_error(&e, args.verbose_errors)
+                );
             }
         }
+        write_html_report_if_requested(&args.docs_path, args.html_report.as_deref())?;
         return Ok(());
     }
 
-    ensure_market_data_repository()?;
+    ensure_market_data_repository_with_options(data)?;
+
+    if args.watch {
+        return watch_score_files(&args.docs_path, &index_update_options, args.verbose_errors);
+    }
 
     // Read the index to get all score files
     let index_data = read_index_json(&args.docs_path)?;
     info!("Found {} score files to process", index_data.scores.len());
 
     // Filter score files by age, empty CSVs, or --process-all.
-    let current_date = Utc::now().naive_utc().date();
-    let scores_to_process: Vec<_> = if args.process_all {
+    let current_date = projection_options.reference_date();
+    let mut scores_to_process: Vec<_> = if args.process_all {
         index_data.scores.iter().collect()
     } else if args.regenerate_empty {
         index_data
@@ -247,7 +1676,11 @@ fn main() -> Result<()> {
             .filter(|score_entry| {
                 build_score_file_path(&args.docs_path, &score_entry.file)
                     .map(|score_file_path| {
-                        is_market_data_csv_empty(&derive_csv_output_path(&score_file_path))
+                        is_market_data_csv_empty(&derive_csv_output_path_with_options(
+                            &score_file_path,
+                            CsvKind::Market,
+                            data,
+                        ))
                     })
                     .unwrap_or(false)
             })
@@ -256,14 +1689,19 @@ fn main() -> Result<()> {
         index_data
             .scores
             .iter()
-            .filter(|score_entry| {
-                if let Ok(score_date) = NaiveDate::parse_from_str(&score_entry.date, "%Y-%m-%d") {
-                    let days_since_score = (current_date - score_date).num_days();
-                    days_since_score <= 180
-                } else {
-                    false
-                }
-            })
+            .filter(
+                |score_entry| match NaiveDate::parse_from_str(&score_entry.date, "%Y-%m-%d") {
+                    Ok(score_date) => (current_date - score_date).num_days() <= 180,
+                    Err(e) => {
+                        log::warn!(
+                            "Skipping {}: invalid date '{}': {e}",
+                            score_entry.file,
+                            score_entry.date
+                        );
+                        false
+                    }
+                },
+            )
             .collect()
     };
 
@@ -285,12 +1723,71 @@ fn main() -> Result<()> {
         );
     }
 
+    // Narrow the selection to the requested --since/--until window (inclusive).
+    if args.since.is_some() || args.until.is_some() {
+        let before = scores_to_process.len();
+        scores_to_process
+            .retain(|score_entry| score_date_in_range(&score_entry.date, args.since, args.until));
+        info!(
+            "Filtered to {} score files within {}..={} ({} outside the range)",
+            scores_to_process.len(),
+            args.since.map_or("start".to_string(), |d| d.to_string()),
+            args.until.map_or("end".to_string(), |d| d.to_string()),
+            before - scores_to_process.len()
+        );
+    }
+
+    if let Some(limit) = args.limit {
+        if scores_to_process.len() > limit {
+            info!(
+                "Limited to the first {limit} of {} score files",
+                scores_to_process.len()
+            );
+            scores_to_process.truncate(limit);
+        }
+    }
+
+    // Every processed stock across all dates, for the overall correlation.
+    let mut all_performances = Vec::new();
+    // Missing-data problems across all dates, for --fail-on-missing-data.
+    let mut missing_data = Vec::new();
+    // Generated CSVs that failed --verify.
+    let mut unverified_csvs = Vec::new();
+
+    let progress = if args.progress {
+        let bar = multi_progress.add(ProgressBar::new(scores_to_process.len() as u64));
+        bar.set_style(
+            ProgressStyle::with_template("processed {pos} / {len} score files {wide_bar}")
+                .expect("progress template is valid"),
+        );
+        bar
+    } else {
+        ProgressBar::hidden()
+    };
+
+    let run_started = Instant::now();
+    let mut stats = RunStats {
+        processed: scores_to_process.len(),
+        index_write_time: args.as_of.is_none().then_some(Duration::ZERO),
+        ..RunStats::default()
+    };
+
     // Process each score file
     for (i, score_entry) in scores_to_process.iter().enumerate() {
-        let score_file_path = match build_score_file_path(&args.docs_path, &score_entry.file) {
+        progress.set_position(i as u64);
+        let score_file_path = match args
+            .error_policy
+            .apply(|| existing_score_file_path(&args.docs_path, &score_entry.file))
+            .with_context(|| format!("Unusable score file path {}", score_entry.file))?
+        {
             Ok(path) => path,
             Err(e) => {
-                log::error!("Skipping unsafe score file path {}: {e}", score_entry.file);
+                log::warn!(
+                    "Skipping {}: {}",
+                    score_entry.file,
+                    format_error(&e, args.verbose_errors)
+                );
+                stats.skipped += 1;
                 continue;
             }
         };
@@ -298,59 +1795,189 @@ fn main() -> Result<()> {
         info!(
             "Processing score file {}/{}: {}",
             i + 1,
-            index_data.scores.len(),
+            scores_to_process.len(),
             score_file_path
         );
         info!("Score file date: {}", score_entry.date);
 
         // Extract ticker codes from the score file
-        match extract_ticker_codes_from_score_file(&score_file_path) {
+        match args
+            .error_policy
+            .apply(|| extract_ticker_codes_from_score_file(&score_file_path))
+            .with_context(|| format!("Failed to read ticker codes from {score_file_path}"))?
+        {
+            Ok(ticker_codes) if ticker_codes.is_empty() => {
+                // A header-only file has no portfolio; skip it rather than
+                // recording a misleading 0% in index.json.
+                log::warn!("Skipping {score_file_path}: no stock records in file");
+                stats.skipped += 1;
+            }
             Ok(ticker_codes) => {
                 info!("Found {} ticker codes in score file", ticker_codes.len());
 
-                // Create CSV file with market data in long format in the same directory as the score file
-                match create_market_data_long_csv_for_score_file(
-                    &score_file_path,
-                    &ticker_codes,
-                    &score_entry.date,
-                    None,
-                ) {
-                    Ok(output_path) => {
-                        info!("Successfully created market data CSV: {output_path}");
-                    }
-                    Err(e) => {
-                        log::error!("Failed to create market data CSV: {e}");
+                // Create CSV file with market data in long format in the same
+                // directory as the score file. An --as-of replay without
+                // --artifacts-dir reads the CSVs already there instead.
+                if write_artifacts {
+                    match args
+                        .error_policy
+                        .apply(|| {
+                            create_market_data_long_csv_for_score_file_with_options(
+                                &score_file_path,
+                                &ticker_codes,
+                                &score_entry.date,
+                                None,
+                                data,
+                            )
+                        })
+                        .with_context(|| {
+                            format!("Failed to create market data CSV for {score_file_path}")
+                        })? {
+                        Ok(output_path) => {
+                            info!("Successfully created market data CSV: {output_path}");
+                            match tickers_missing_from_csv(&output_path, &ticker_codes) {
+                                Ok(missing) if missing.is_empty() => {
+                                    info!(
+                                        "Market data CSV covers all {} tickers",
+                                        ticker_codes.len()
+                                    );
+                                }
+                                // Already logged with the missing tickers.
+                                Ok(_) => {}
+                                Err(e) => log::warn!(
+                                    "Could not check ticker coverage of {output_path}: {}",
+                                    format_error(&e, args.verbose_errors)
+                                ),
+                            }
+                            if args.verify && !verify_csv(&output_path, CsvKind::Market) {
+                                unverified_csvs.push(output_path);
+                            }
+                        }
+                        Err(e @ GrqError::StrictModeViolation(_)) => {
+                            return Err(e).with_context(|| {
+                                format!("Failed to create market data CSV for {score_file_path}")
+                            });
+                        }
+                        Err(e) => {
+                            log::error!(
+                                "Failed to create market data CSV: {}",
+                                format_error(&e, args.verbose_errors)
+                            );
+                        }
                     }
                 }
 
-                // Create dividend CSV file
-                match create_dividend_csv_for_score_file(
-                    &score_file_path,
-                    &ticker_codes,
-                    &score_entry.date,
-                ) {
-                    Ok(_) => {
-                        info!("Successfully created dividend CSV for {score_file_path}");
-                    }
-                    Err(e) => {
-                        log::error!("Failed to create dividend CSV: {e}");
+                // Create dividend CSV file, keeping the dividends it read for
+                // the performance calculation below
+                let mut file_options = performance_options.clone();
+                if write_artifacts {
+                    match args
+                        .error_policy
+                        .apply(|| {
+                            create_dividend_csv_for_score_file_with_options(
+                                &score_file_path,
+                                &ticker_codes,
+                                &score_entry.date,
+                                data,
+                            )
+                        })
+                        .with_context(|| {
+                            format!("Failed to create dividend CSV for {score_file_path}")
+                        })? {
+                        Ok(dividends) => {
+                            file_options.shared_dividends = Some(dividends);
+                            info!("Successfully created dividend CSV for {score_file_path}");
+                            let output_path = derive_csv_output_path_with_options(
+                                &score_file_path,
+                                CsvKind::Dividends,
+                                data,
+                            );
+                            if args.verify && !verify_csv(&output_path, CsvKind::Dividends) {
+                                unverified_csvs.push(output_path);
+                            }
+                        }
+                        Err(e @ GrqError::StrictModeViolation(_)) => {
+                            return Err(e).with_context(|| {
+                                format!("Failed to create dividend CSV for {score_file_path}")
+                            });
+                        }
+                        Err(e) => {
+                            log::error!(
+                                "Failed to create dividend CSV: {}",
+                                format_error(&e, args.verbose_errors)
+                            );
+                        }
                     }
                 }
 
+                if args.fail_on_missing_data {
+                    missing_data.extend(missing_data_problems(
+                        &score_file_path,
+                        &score_entry.date,
+                        args.max_buy_gap_days,
+                        data,
+                    ));
+                }
+
                 // Calculate performance for this score file immediately after creating CSVs
                 info!("Calculating performance for {}", score_entry.date);
-                match grq_validation::utils::calculate_portfolio_performance(
-                    &score_file_path,
-                    &score_entry.date,
-                ) {
+                let is_mature = NaiveDate::parse_from_str(&score_entry.date, "%Y-%m-%d")
+                    .is_ok_and(|score_date| (current_date - score_date).num_days() >= 90);
+                match args
+                    .error_policy
+                    .apply(|| {
+                        grq_validation::utils::calculate_portfolio_performance_with_options(
+                            &score_file_path,
+                            &score_entry.date,
+                            &file_options,
+                        )
+                    })
+                    .or_else(keep_too_few_stocks)
+                    .with_context(|| {
+                        format!("Failed to calculate performance for {}", score_entry.date)
+                    })? {
                     Ok(performance) => {
                         info!(
-                            "Performance for {}: {:.2}% (90-day), {:.2}% (annualized), {} included stocks",
+                            "Performance for {}: {:.2}% (90-day), {:.2}% (annualized {}), {} included stocks",
                             score_entry.date,
                             performance.performance_90_day,
                             performance.performance_annualized,
+                            performance.annualization_label(),
                             performance.total_stocks
                         );
+                        info!(
+                            "{} for {}",
+                            performance.winners_losers_summary(),
+                            score_entry.date
+                        );
+                        info!(
+                            "Score/return correlation for {}: {}",
+                            score_entry.date,
+                            format_correlation(score_return_correlation(
+                                &performance.individual_performances
+                            ))
+                        );
+                        // Each stock performance carries its own score, so
+                        // no score records are needed to bucket them.
+                        info!(
+                            "Return by score bucket for {}: {}",
+                            score_entry.date,
+                            format_score_bucket_returns(&score_bucket_returns(
+                                &[],
+                                &performance.individual_performances
+                            ))
+                        );
+                        info!(
+                            "{} for {}",
+                            format_risk_summary(&performance, args.risk_free_rate),
+                            score_entry.date
+                        );
+                        all_performances
+                            .extend(performance.individual_performances.iter().cloned());
+                        stats.annualized.push(performance.performance_annualized);
+                        if performance.excluded_tickers.is_empty() {
+                            stats.full_data += 1;
+                        }
                         if !performance.excluded_tickers.is_empty() {
                             info!(
                                 "Excluded {} unpriceable stocks for {}",
@@ -358,45 +1985,140 @@ fn main() -> Result<()> {
                                 score_entry.date
                             );
                         }
+                        let horizons = match calculate_horizon_returns(
+                            &score_file_path,
+                            &score_entry.date,
+                            &file_options,
+                        ) {
+                            Ok(horizons) => {
+                                info!(
+                                    "Horizon returns for {}: {:.2}% (30d), {:.2}% (60d), {:.2}% (90d)",
+                                    score_entry.date,
+                                    horizons.return_30d,
+                                    horizons.return_60d,
+                                    horizons.return_90d
+                                );
+                                Some(horizons)
+                            }
+                            Err(e) => {
+                                log::warn!(
+                                    "Failed to calculate horizon returns for {}: {}",
+                                    score_entry.date,
+                                    format_error(&e, args.verbose_errors)
+                                );
+                                None
+                            }
+                        };
+
+                        if args.write_detail && write_artifacts {
+                            match write_performance_detail(&score_file_path, &performance, data) {
+                                Ok(detail_path) => {
+                                    info!("Wrote performance detail to {detail_path}");
+                                }
+                                Err(e) => {
+                                    log::error!(
+                                        "Failed to write performance detail: {}",
+                                        format_error(&e, args.verbose_errors)
+                                    );
+                                }
+                            }
+                        }
 
                         // Update the index.json with this performance data
-                        let mut index_data =
-                            grq_validation::utils::read_index_json(&args.docs_path)?;
-                        for score_entry_update in &mut index_data.scores {
-                            if score_entry_update.date == score_entry.date {
+                        let index_write_started = Instant::now();
+                        update_index_entry(
+                            &args.docs_path,
+                            &score_entry.date,
+                            args.as_of,
+                            |score_entry_update| {
                                 score_entry_update.performance_90_day =
                                     Some(performance.performance_90_day);
                                 score_entry_update.performance_annualized =
                                     Some(performance.performance_annualized);
                                 score_entry_update.total_stocks = Some(performance.total_stocks);
-                                break;
-                            }
+                                // Horizon returns mark an entry as settled, so
+                                // a score under 90 days old must not carry
+                                // them or it would never be recomputed once it
+                                // matures.
+                                match &horizons {
+                                    Some(horizons) if is_mature => {
+                                        score_entry_update.set_horizon_returns(horizons);
+                                    }
+                                    _ => score_entry_update.clear_horizon_returns(),
+                                }
+                            },
+                        )?;
+                        if let Some(index_write_time) = &mut stats.index_write_time {
+                            *index_write_time += index_write_started.elapsed();
                         }
-
-                        // Write updated index back to file
-                        let index_path =
-                            Path::new(&args.docs_path).join("scores").join("index.json");
-                        let json_content = serde_json::to_string_pretty(&index_data)?;
-                        std::fs::write(index_path, json_content)?;
-                        info!(
-                            "Updated index.json with performance data for {}",
-                            score_entry.date
-                        );
+                    }
+                    Err(e @ GrqError::TooFewStocks { .. }) => {
+                        log::warn!("Leaving {} unset: {e}", score_entry.date);
+                        stats.skipped += 1;
+                        update_index_entry(
+                            &args.docs_path,
+                            &score_entry.date,
+                            args.as_of,
+                            ScoreEntry::clear_performance,
+                        )?;
+                    }
+                    Err(e @ GrqError::StrictModeViolation(_)) => {
+                        return Err(e).with_context(|| {
+                            format!("Failed to calculate performance for {}", score_entry.date)
+                        });
                     }
                     Err(e) => {
                         log::error!(
                             "Failed to calculate performance for {}: {}",
                             score_entry.date,
-                            e
+                            format_error(&e, args.verbose_errors)
                         );
+                        stats.errors += 1;
                     }
                 }
             }
             Err(e) => {
-                log::error!("Failed to read ticker codes from {score_file_path}: {e}");
+                log::error!(
+                    "Failed to read ticker codes from {score_file_path}: {}",
+                    format_error(&e, args.verbose_errors)
+                );
+                stats.errors += 1;
             }
         }
     }
+    progress.finish_and_clear();
+    info!("{}", stats.summary(run_started.elapsed()));
+
+    if !all_performances.is_empty() {
+        info!(
+            "Score/return correlation across {} stocks: {}",
+            all_performances.len(),
+            format_correlation(score_return_correlation(&all_performances))
+        );
+    }
+
+    if args.fail_on_missing_data {
+        fail_on_missing_data(&missing_data)?;
+    }
+    if !unverified_csvs.is_empty() {
+        return Err(anyhow!(
+            "{} generated CSVs failed verification (--verify): {}",
+            unverified_csvs.len(),
+            unverified_csvs.join(", ")
+        ));
+    }
+
+    match read_index_json(&args.docs_path)
+        .map(|index_data| overall_summary_as_of(&index_data, current_date))
+    {
+        Ok(summary) => info!("{}", format_overall_summary(&summary)),
+        Err(e) => log::warn!(
+            "Failed to summarise index.json: {}",
+            format_error(&e, args.verbose_errors)
+        ),
+    }
+
+    write_html_report_if_requested(&args.docs_path, args.html_report.as_deref())?;
 
     info!("GRQ Validation processor completed successfully");
     Ok(())
@@ -1,21 +1,78 @@
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+/// How currency values are written out: `-$1,234.56` by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyFormat {
+    /// Digits after the decimal point; the value is rounded to fit.
+    pub precision: usize,
+    /// Group the whole dollars in threes with commas.
+    pub thousands_separator: bool,
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        Self {
+            precision: 2,
+            thousands_separator: true,
+        }
+    }
+}
+
+impl CurrencyFormat {
+    /// Formats `value` with a leading dollar sign, after the minus sign for
+    /// negative amounts, e.g. `-$45,749.70`. A value that rounds to zero is
+    /// written without a sign.
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return format!("${value}");
+        }
+        let precision = self.precision;
+        let digits = format!("{:.precision$}", value.abs());
+
+        let (whole, fraction) = match digits.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+        let mut formatted = String::with_capacity(digits.len() + whole.len() / 3 + 2);
+        let is_zero = digits.bytes().all(|b| b == b'0' || b == b'.');
+        if value < 0.0 && !is_zero {
+            formatted.push('-');
+        }
+        formatted.push('$');
+        for (i, digit) in whole.chars().enumerate() {
+            if self.thousands_separator && i > 0 && (whole.len() - i) % 3 == 0 {
+                formatted.push(',');
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push('.');
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+}
+
 /// Custom serializer for currency values that formats them with dollar signs and commas
 fn serialize_currency<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    // Format with dollar sign and commas for thousands
-    let formatted = format!("${value:.2}");
-    serializer.serialize_str(&formatted)
+    serializer.serialize_str(&CurrencyFormat::default().format(*value))
 }
 
-/// Custom deserializer for currency values that may contain dollar signs and commas
+/// Custom deserializer for currency values that may contain dollar signs and commas.
+/// A blank value reads as `0.0` so a row missing its target still loads and is
+/// reported by [`crate::utils::find_invalid_targets`] instead of failing the file.
 fn deserialize_currency<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let s: String = Deserialize::deserialize(deserializer)?;
+    if s.trim().is_empty() {
+        return Ok(0.0);
+    }
 
     // Remove dollar sign and commas, then parse as float
     // Handle negative values with currency formatting like "-$45,749.70"
@@ -28,17 +85,42 @@ where
     })
 }
 
+/// Custom deserializer for scores, which some files write as a percentage
+/// (`"95%"`) rather than a fraction (`"0.95"`). A trailing `%` is stripped and
+/// the value divided by 100; bare numbers pass through unchanged.
+fn deserialize_score<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawScore {
+        Number(f64),
+        Text(String),
+    }
+
+    let text = match RawScore::deserialize(deserializer)? {
+        RawScore::Number(score) => return Ok(score),
+        RawScore::Text(text) => text,
+    };
+    let trimmed = text.trim();
+    let (number, scale) = match trimmed.strip_suffix('%') {
+        Some(percent) => (percent.trim_end(), 100.0),
+        None => (trimmed, 1.0),
+    };
+    number
+        .parse::<f64>()
+        .map(|score| score / scale)
+        .map_err(|e| serde::de::Error::custom(format!("Failed to parse score '{text}': {e}")))
+}
+
 /// Custom serializer for optional currency values
 fn serialize_optional_currency<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
     match value {
-        Some(v) => {
-            // Format with dollar sign and commas for thousands
-            let formatted = format!("${v:.2}");
-            serializer.serialize_str(&formatted)
-        }
+        Some(v) => serializer.serialize_str(&CurrencyFormat::default().format(*v)),
         None => serializer.serialize_none(),
     }
 }
@@ -76,10 +158,12 @@ pub struct StockRecord {
     /// Full ticker symbol, e.g. `"NYSE:SEM"`.
     #[serde(rename = "Stock")]
     pub stock: String,
-    /// Analyst score for the stock.
-    #[serde(rename = "Score")]
+    /// Analyst score for the stock, from `0` to `1`; a percentage such as
+    /// `"95%"` reads as `0.95`.
+    #[serde(rename = "Score", deserialize_with = "deserialize_score")]
     pub score: f64,
-    /// Target price (parsed from currency-formatted text such as `"$22.63"`).
+    /// Target price (parsed from currency-formatted text such as `"$22.63"`);
+    /// `0.0` when the file leaves it blank.
     #[serde(
         rename = "Target",
         serialize_with = "serialize_currency",
@@ -128,6 +212,108 @@ impl StockRecord {
     }
 }
 
+/// A position size parsed from a score file's `Notes`, e.g.
+/// `"Buy 235 at $45.44 ~= $10,678"`. Share counts may be fractional
+/// (`"Buy 12.5 at $80.00"`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositionSizing {
+    /// Number of shares bought (may be fractional).
+    pub shares: f64,
+    /// Price paid per share.
+    pub price: f64,
+}
+
+impl PositionSizing {
+    /// Parses a `Buy <shares> at $<price>` note. Thousands separators are
+    /// accepted and anything after the price (the `~= $total` estimate, a
+    /// running P&L) is ignored. Returns `None` for any other note, or for a
+    /// non-positive share count or price.
+    pub fn parse(notes: &str) -> Option<Self> {
+        let parse_number = |raw: &str| raw.replace(',', "").parse::<f64>().ok();
+        let mut tokens = notes.split_whitespace();
+        if !tokens.next()?.eq_ignore_ascii_case("buy") {
+            return None;
+        }
+        let shares = parse_number(tokens.next()?)?;
+        if !tokens.next()?.eq_ignore_ascii_case("at") {
+            return None;
+        }
+        let price = parse_number(tokens.next()?.trim_start_matches('$'))?;
+
+        (shares.is_finite() && shares > 0.0 && price.is_finite() && price > 0.0)
+            .then_some(Self { shares, price })
+    }
+
+    /// Total cost of the position: `shares * price`.
+    pub fn cost_basis(&self) -> f64 {
+        self.shares * self.price
+    }
+}
+
+/// One buy in a paper-trade export, as a broker import CSV lists it (see
+/// [`crate::utils::paper_trades`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperTrade {
+    /// Bare symbol without its exchange prefix, e.g. `SEM` for `NYSE:SEM`.
+    pub symbol: String,
+    /// Number of shares bought (may be fractional).
+    pub quantity: f64,
+    /// Price paid per share.
+    pub price: f64,
+    /// Date of the close the shares were bought at.
+    pub date: NaiveDate,
+}
+
+/// Dollar value of the sized positions in a portfolio at the start and end of
+/// its window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DollarPortfolioValue {
+    /// Number of positions with a parsed size and usable start and end prices.
+    pub positions: usize,
+    /// Total value of those positions at their buy prices.
+    pub start_value: f64,
+    /// Total value of those positions at the end of the window.
+    pub end_value: f64,
+}
+
+impl DollarPortfolioValue {
+    /// Absolute profit or loss in dollars: `end_value - start_value`.
+    pub fn pnl(&self) -> f64 {
+        self.end_value - self.start_value
+    }
+
+    /// Profit or loss as a percentage of `start_value` (`0.0` when nothing was
+    /// invested).
+    pub fn pnl_percent(&self) -> f64 {
+        if self.start_value > 0.0 {
+            self.pnl() / self.start_value * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Share of `total_capital` put into the positions: `start_value /
+    /// total_capital` (`0.0` for a non-positive capital).
+    pub fn invested_fraction(&self, total_capital: f64) -> f64 {
+        if total_capital > 0.0 {
+            self.start_value / total_capital
+        } else {
+            0.0
+        }
+    }
+
+    /// Return on the whole of `total_capital` as a percentage, treating the
+    /// `total_capital - start_value` not put into positions as cash earning
+    /// nothing (`0.0` for a non-positive capital).
+    pub fn blended_return_percent(&self, total_capital: f64) -> f64 {
+        if total_capital > 0.0 {
+            self.pnl() / total_capital * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Metadata block of an Alpha Vantage daily time-series JSON file.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MarketDataMeta {
@@ -180,10 +366,13 @@ pub struct DailyData {
 /// Split-relevant daily figures parsed from the derived market-data CSV.
 ///
 /// `high`/`low` feed the price-ratio reconciliation cross-check used to judge
-/// whether a split series can be trusted (issue #294). The close price is held
-/// separately in [`MarketDataCsv::closes`] and is not duplicated here.
+/// whether a split series can be trusted (issue #294); `open` supports the
+/// next-open entry price. The close price is held separately in
+/// [`MarketDataCsv::closes`] and is not duplicated here.
 #[derive(Debug, Clone, PartialEq)]
 pub struct DailyMarketPoint {
+    /// Opening price for the day (`None` when blank or non-numeric).
+    pub open: Option<f64>,
     /// Highest traded price for the day.
     pub high: f64,
     /// Lowest traded price for the day.
@@ -194,18 +383,156 @@ pub struct DailyMarketPoint {
     pub volume: Option<f64>,
 }
 
+/// One day's prices for a ticker, joined from [`MarketDataCsv::closes`] and
+/// [`MarketDataCsv::points`] (see [`MarketDataCsv::ohlc`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ohlc {
+    /// Opening price (`None` when blank or non-numeric in the CSV).
+    pub open: Option<f64>,
+    /// Highest traded price.
+    pub high: f64,
+    /// Lowest traded price.
+    pub low: f64,
+    /// Closing price.
+    pub close: f64,
+}
+
 /// Result of parsing a derived market-data CSV.
 ///
-/// `closes` preserves the original `ticker -> date -> close` shape consumed by
-/// existing callers; `points` carries the split-relevant figures used to
-/// correct-or-exclude split-distorted stocks (issue #294).
+/// `closes` holds the `ticker -> date -> close` series; `points` carries the
+/// split-relevant figures used to correct-or-exclude split-distorted stocks
+/// (issue #294). Dates are parsed once at load and each series is ordered by
+/// date, so "first price on or after" and "latest price within a window" are
+/// range scans rather than reparsing every key.
 #[derive(Debug, Default)]
 pub struct MarketDataCsv {
-    /// `ticker -> date -> close price`.
-    pub closes: std::collections::HashMap<String, std::collections::HashMap<String, f64>>,
-    /// `ticker -> date -> split-relevant daily figures`.
+    /// `ticker -> date -> close price`, ordered by date.
+    pub closes: std::collections::HashMap<String, std::collections::BTreeMap<NaiveDate, f64>>,
+    /// `ticker -> date -> split-relevant daily figures`, ordered by date.
     pub points:
-        std::collections::HashMap<String, std::collections::HashMap<String, DailyMarketPoint>>,
+        std::collections::HashMap<String, std::collections::BTreeMap<NaiveDate, DailyMarketPoint>>,
+    /// `(ticker, date)` rows dropped for a zero or negative close, in file order.
+    pub rejected_prices: Vec<(String, String)>,
+    /// `(ticker, date)` rows dropped because an earlier row already gave that
+    /// ticker's figures for the date, in file order.
+    pub duplicate_rows: Vec<(String, String)>,
+}
+
+impl MarketDataCsv {
+    /// Joins `closes` and `points` into a `ticker -> date -> OHLC` view for
+    /// analyses that need the full daily bar (intraday target hits, next-open
+    /// entries, true-range volatility).
+    pub fn ohlc(
+        &self,
+    ) -> std::collections::HashMap<String, std::collections::BTreeMap<NaiveDate, Ohlc>> {
+        self.closes
+            .iter()
+            .map(|(ticker, closes)| {
+                let points = self.points.get(ticker);
+                let bars = closes
+                    .iter()
+                    .map(|(date, close)| {
+                        let point = points.and_then(|points| points.get(date));
+                        let bar = Ohlc {
+                            open: point.and_then(|point| point.open),
+                            high: point.map_or(*close, |point| point.high),
+                            low: point.map_or(*close, |point| point.low),
+                            close: *close,
+                        };
+                        (*date, bar)
+                    })
+                    .collect();
+                (ticker.clone(), bars)
+            })
+            .collect()
+    }
+}
+
+/// What [`MissingData`] is missing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingDataKind {
+    /// No close on, or within the buy gap after, the score date.
+    Price,
+    /// A dividend file exists but could not be read. A stock without a
+    /// dividend file simply pays none and is not reported.
+    Dividend,
+}
+
+/// A stock in a score file whose price or dividend data is unavailable (see
+/// [`crate::utils::find_missing_data`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissingData {
+    /// Score date in `YYYY-MM-DD` form.
+    pub date: String,
+    /// Full ticker from the score file.
+    pub ticker: String,
+    /// Which data is missing.
+    pub kind: MissingDataKind,
+}
+
+impl std::fmt::Display for MissingData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let what = match self.kind {
+            MissingDataKind::Price => "no market data",
+            MissingDataKind::Dividend => "unreadable dividend data",
+        };
+        write!(f, "{} {}: {what}", self.date, self.ticker)
+    }
+}
+
+/// A problem found while linting a score file or a generated CSV (see
+/// [`crate::utils::validate_score_file`] and
+/// [`crate::utils::verify_generated_csv`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreFileIssue {
+    /// Path of the file the problem was found in.
+    pub file: String,
+    /// 1-based line number in the file (the header is line 1), when the
+    /// problem is tied to a specific row.
+    pub row: Option<u64>,
+    /// Human-readable description of the problem.
+    pub message: String,
+}
+
+impl std::fmt::Display for ScoreFileIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.row {
+            Some(row) => write!(f, "{}:{row}: {}", self.file, self.message),
+            None => write!(f, "{}: {}", self.file, self.message),
+        }
+    }
+}
+
+/// A tenth-wide range of model scores, `[n/10, (n+1)/10)`, that
+/// [`crate::utils::score_bucket_returns`] groups stocks by. A score of `1.0`
+/// falls in the top bucket, `0.9–1.0`. Buckets order from lowest to highest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScoreBucket(u8);
+
+impl ScoreBucket {
+    /// The bucket holding `score`; scores outside `[0, 1]` go to the nearest
+    /// end bucket.
+    pub fn for_score(score: f64) -> Self {
+        // The small nudge keeps e.g. 0.3 (0.2999… in binary) out of 0.2–0.3.
+        let tenth = ((score * 10.0) + 1e-9).floor().clamp(0.0, 9.0);
+        Self(tenth as u8)
+    }
+
+    /// Lowest score in the bucket.
+    pub fn lower(self) -> f64 {
+        f64::from(self.0) / 10.0
+    }
+
+    /// Score the bucket runs up to (exclusive, except for `1.0`).
+    pub fn upper(self) -> f64 {
+        f64::from(self.0 + 1) / 10.0
+    }
+}
+
+impl std::fmt::Display for ScoreBucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1}–{:.1}", self.lower(), self.upper())
+    }
 }
 
 /// A full market-data file: metadata plus the daily time series keyed by date.
@@ -220,15 +547,64 @@ pub struct MarketData {
 }
 
 /// Top-level structure of `docs/scores/index.json`.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IndexData {
+    /// When the index was last written, stamped by
+    /// [`crate::utils::write_index_atomic`]; absent from older files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Version of this tool that last wrote the index; absent from older files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator_version: Option<String>,
     /// All known score entries, one per daily score file.
     pub scores: Vec<ScoreEntry>,
 }
 
+impl IndexData {
+    /// Records the current time and this tool's version as the index's
+    /// generation stamp.
+    pub fn stamp_generation(&mut self) {
+        self.generated_at = Some(chrono::Utc::now());
+        self.generator_version = Some(env!("CARGO_PKG_VERSION").to_string());
+    }
+}
+
+/// All-time headline figures across every completed (at least 90-day-old)
+/// score file in the index (see [`crate::utils::overall_summary`]).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OverallSummary {
+    /// Number of completed score dates with an annualised figure.
+    pub total_dates: usize,
+    /// Mean annualised performance, as a percentage (`None` without dates).
+    pub mean_annualized: Option<f64>,
+    /// Median annualised performance, as a percentage (`None` without dates).
+    pub median_annualized: Option<f64>,
+    /// Score date with the highest annualised performance.
+    pub best_date: Option<String>,
+    /// Score date with the lowest annualised performance.
+    pub worst_date: Option<String>,
+}
+
+/// A date whose headline figures differ between two `index.json` files (see
+/// [`crate::utils::diff_index`]). A side is `None` when that index has no
+/// figure for the date, including when it lacks the date altogether.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexChange {
+    /// Score date in `YYYY-MM-DD` form.
+    pub date: String,
+    /// 90-day performance in the old index.
+    pub old_performance_90_day: Option<f64>,
+    /// 90-day performance in the new index.
+    pub new_performance_90_day: Option<f64>,
+    /// Annualised performance in the old index.
+    pub old_performance_annualized: Option<f64>,
+    /// Annualised performance in the new index.
+    pub new_performance_annualized: Option<f64>,
+}
+
 /// A single entry in the scores index, describing one daily score file and its
 /// computed performance.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoreEntry {
     /// Year component of the score date.
     #[serde(rename = "year")]
@@ -257,10 +633,60 @@ pub struct ScoreEntry {
     /// Number of stocks contributing to the performance figures.
     #[serde(rename = "total_stocks", skip_serializing_if = "Option::is_none")]
     pub total_stocks: Option<i32>,
+    /// Average portfolio return at the 30-day horizon, once calculated, as
+    /// total or price-only return per the run's `--return-type`.
+    #[serde(rename = "return_30d", skip_serializing_if = "Option::is_none")]
+    pub return_30d: Option<f64>,
+    /// Average portfolio return at the 60-day horizon, once calculated, as
+    /// total or price-only return per the run's `--return-type`.
+    #[serde(rename = "return_60d", skip_serializing_if = "Option::is_none")]
+    pub return_60d: Option<f64>,
+    /// Average portfolio return at the 90-day horizon, once calculated, as
+    /// total or price-only return per the run's `--return-type`.
+    #[serde(rename = "return_90d", skip_serializing_if = "Option::is_none")]
+    pub return_90d: Option<f64>,
+}
+
+impl ScoreEntry {
+    /// The score date, parsed. The archive has entries with an unpadded day
+    /// (`2024-12-3`), which parse to the same date as the padded form, so
+    /// entries should be matched by this rather than by [`Self::date`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the parse error if [`Self::date`] is not a `YYYY-MM-DD` date.
+    pub fn parsed_date(&self) -> chrono::ParseResult<NaiveDate> {
+        NaiveDate::parse_from_str(&self.date, "%Y-%m-%d")
+    }
+
+    /// Records the 30/60/90-day horizon returns on this entry.
+    pub fn set_horizon_returns(&mut self, horizons: &HorizonReturns) {
+        self.return_30d = Some(horizons.return_30d);
+        self.return_60d = Some(horizons.return_60d);
+        self.return_90d = Some(horizons.return_90d);
+    }
+
+    /// Drops the entry's performance figures and horizon returns, leaving it
+    /// as if it had never been calculated.
+    pub fn clear_performance(&mut self) {
+        self.performance_90_day = None;
+        self.performance_annualized = None;
+        self.total_stocks = None;
+        self.clear_horizon_returns();
+    }
+
+    /// Drops any horizon returns, as for an entry whose figures are a
+    /// projection. A recorded `return_90d` marks the entry as settled, so a
+    /// still-open score must not carry one.
+    pub fn clear_horizon_returns(&mut self) {
+        self.return_30d = None;
+        self.return_60d = None;
+        self.return_90d = None;
+    }
 }
 
 /// A single dividend event for a stock.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DividendRecord {
     /// Ex-dividend date in `YYYY-MM-DD` form.
     #[serde(rename = "ex_dividend_date")]
@@ -277,10 +703,15 @@ pub struct DividendRecord {
     /// Dividend amount per share, as raw text.
     #[serde(rename = "amount")]
     pub amount: String,
+    /// ISO currency of `amount` (`"GBX"`/`"GBp"` for pence). When absent the
+    /// amount is taken to be in the stock's own quote units, i.e. USD for US
+    /// listings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
 }
 
 /// All dividend events for a single stock.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DividendData {
     /// Ticker symbol the dividends belong to.
     pub symbol: String,
@@ -289,10 +720,13 @@ pub struct DividendData {
 }
 
 /// Computed 90-day performance for a single stock within a portfolio.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StockPerformance {
     /// Full ticker symbol.
     pub ticker: String,
+    /// Model score from the score file, kept so realised returns can be
+    /// checked against it.
+    pub score: f64,
     /// Buy price (close on, or just after, the score date).
     pub buy_price: f64,
     /// Analyst target price from the score file.
@@ -303,12 +737,226 @@ pub struct StockPerformance {
     pub gain_loss_percent: f64,
     /// Total dividends received over the period.
     pub dividends_total: f64,
+    /// Each dividend making up [`Self::dividends_total`], as `(ex_date,
+    /// amount)` oldest first.
+    pub dividends: Vec<(NaiveDate, f64)>,
     /// Total return (price plus dividends), as a percentage.
     pub total_return_percent: f64,
+    /// Reinvested total return minus the cash-dividend total return, in
+    /// percentage points. `None` unless dividend reinvestment was requested.
+    pub reinvestment_delta_percent: Option<f64>,
+    /// Upside implied at buy time by the adjusted intrinsic value,
+    /// `(intrinsic - buy_price) / buy_price`, as a percentage. `None` when the
+    /// score file has no adjusted intrinsic value for the stock.
+    pub intrinsic_upside_percent: Option<f64>,
+    /// How far the current price has moved from the buy price towards the
+    /// target, as a percentage of that distance (`100` = target reached).
+    /// A target at or below the buy price counts as met at entry and reports
+    /// `100` plus the price gain since. `None` without a target. See
+    /// [`crate::utils::calculate_progress_to_target`].
+    pub progress_to_target_percent: Option<f64>,
+    /// Standard deviation of the stock's daily close-to-close changes within
+    /// the window, as a percentage. `0.0` when fewer than two closes are
+    /// available.
+    pub volatility_percent: f64,
+    /// Money-weighted annualised return, as a percentage: the XIRR of the buy
+    /// as an outflow and each dividend and the current price as dated
+    /// inflows. `None` when it cannot be solved (e.g. a same-day window) or
+    /// for a projection.
+    pub irr_percent: Option<f64>,
+    /// First date in the window whose intraday high reached the target price,
+    /// which can be earlier than (or without) a close above it. `None` when
+    /// the target was never touched or for a projection.
+    pub target_hit_date: Option<NaiveDate>,
+    /// Number of closes available within the window.
+    pub data_points: usize,
+    /// Days from the first to the last available close within the window
+    /// (`0` with fewer than two closes), to spot thinly covered picks.
+    pub coverage_days: i64,
+}
+
+impl StockPerformance {
+    /// Replaces any NaN or infinite figure with a finite one (see
+    /// [`crate::utils::sanitize_f64`]), so pathological prices cannot put
+    /// `null` into the JSON written from it.
+    pub fn sanitize(&mut self) {
+        use crate::utils::sanitize_f64;
+
+        for value in [
+            &mut self.score,
+            &mut self.buy_price,
+            &mut self.target_price,
+            &mut self.current_price,
+            &mut self.gain_loss_percent,
+            &mut self.dividends_total,
+            &mut self.total_return_percent,
+            &mut self.volatility_percent,
+        ] {
+            *value = sanitize_f64(*value);
+        }
+        for value in [
+            &mut self.reinvestment_delta_percent,
+            &mut self.intrinsic_upside_percent,
+            &mut self.progress_to_target_percent,
+            &mut self.irr_percent,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *value = sanitize_f64(*value);
+        }
+        for (_, amount) in &mut self.dividends {
+            *amount = sanitize_f64(*amount);
+        }
+    }
+}
+
+/// Average portfolio return, as a percentage, at several horizons after the
+/// score date, measured as total or price-only return per the run's
+/// `ReturnType`. Each horizon falls back to the latest available price
+/// inside its window when the exact horizon date has no close.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HorizonReturns {
+    /// Return over the first 30 days.
+    pub return_30d: f64,
+    /// Return over the first 60 days.
+    pub return_60d: f64,
+    /// Return over the first 90 days.
+    pub return_90d: f64,
+}
+
+/// One ticker's 90-day performance in a score file that picked it.
+#[derive(Debug, Clone)]
+pub struct TickerAppearance {
+    /// Score date of the file the ticker appeared in (`YYYY-MM-DD`).
+    pub score_date: String,
+    /// The ticker's performance over that file's window.
+    pub performance: StockPerformance,
+}
+
+/// How well the hybrid projection made a fixed number of days after the
+/// score date predicted the realised 90-day return (see
+/// [`crate::utils::projection_accuracy`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectionAccuracy {
+    /// Days after the score date the projection was made.
+    pub elapsed_days: i64,
+    /// Number of settled score files compared.
+    pub samples: usize,
+    /// Mean of `|projected - realised|`, in percentage points.
+    pub mean_absolute_error: f64,
+    /// Mean of `projected - realised`, in percentage points; positive means
+    /// the projection ran high.
+    pub bias: f64,
+}
+
+/// A run of trading days missing from a ticker's market data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataGap {
+    /// Last date with data before the gap.
+    pub after: NaiveDate,
+    /// First date with data after the gap.
+    pub before: NaiveDate,
+    /// Trading days strictly between the two without data.
+    pub missing_trading_days: i64,
+}
+
+/// The dates one ticker's market data covers (see
+/// [`crate::utils::market_data_coverage`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketDataCoverage {
+    /// Earliest date with data.
+    pub first_date: NaiveDate,
+    /// Latest date with data.
+    pub last_date: NaiveDate,
+    /// Number of dates with data.
+    pub data_points: usize,
+    /// Gaps longer than the threshold asked for, oldest first.
+    pub gaps: Vec<DataGap>,
+}
+
+/// Summary of a ticker's performance across every score file that picked it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickerSummary {
+    /// Number of priced appearances.
+    pub appearances: usize,
+    /// Mean total return across the appearances, as a percentage (`None`
+    /// without any).
+    pub mean_return: Option<f64>,
+    /// Share of appearances with a positive total return, as a percentage
+    /// (`None` without any).
+    pub win_rate: Option<f64>,
+}
+
+/// One benchmark's return over a portfolio's window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    /// Benchmark symbol (e.g. `SPY`).
+    pub symbol: String,
+    /// Price return over the window, as a percentage.
+    pub return_percent: f64,
+    /// Whether the portfolio's return strictly exceeded this benchmark's.
+    pub beaten: bool,
+}
+
+/// How a portfolio ranks against a set of benchmarks over the same window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkComparison {
+    /// Each benchmark's result, in the order requested.
+    pub benchmarks: Vec<BenchmarkResult>,
+    /// 1-based rank of the portfolio among itself and the benchmarks
+    /// (1 = best); ties share the better rank.
+    pub rank: usize,
+    /// Percentage of benchmarks the portfolio beat.
+    pub percentile: f64,
+}
+
+/// Which days a period return is annualised over: calendar days compounded
+/// over 365.25 a year, or trading days compounded over 252 a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnualizationBasis {
+    /// Calendar days elapsed, 365.25 per year.
+    #[default]
+    Calendar,
+    /// Trading days elapsed, 252 per year.
+    Trading,
+}
+
+impl AnnualizationBasis {
+    /// Days in a year on this basis.
+    pub fn days_per_year(self) -> f64 {
+        match self {
+            Self::Calendar => 365.25,
+            Self::Trading => TRADING_DAYS_PER_YEAR,
+        }
+    }
+
+    /// The kind of day counted, for output labels: `"calendar"` or `"trading"`.
+    pub fn day_kind(self) -> &'static str {
+        match self {
+            Self::Calendar => "calendar",
+            Self::Trading => "trading",
+        }
+    }
+}
+
+impl std::str::FromStr for AnnualizationBasis {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "calendar" => Ok(Self::Calendar),
+            "trading" => Ok(Self::Trading),
+            _ => Err(format!(
+                "unknown annualization basis '{value}' (expected calendar or trading)"
+            )),
+        }
+    }
 }
 
 /// Aggregated performance of a whole portfolio for one score date.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct PortfolioPerformance {
     /// Score date the figures relate to (`YYYY-MM-DD`).
     pub score_date: String,
@@ -318,16 +966,273 @@ pub struct PortfolioPerformance {
     pub performance_90_day: f64,
     /// Annualised equivalent of the 90-day return, as a percentage.
     pub performance_annualized: f64,
+    /// Days `performance_annualized` compounds over: from the score date to
+    /// the latest close used, capped at the window. A hybrid projection
+    /// covers the full 90 days.
+    pub actual_days_elapsed: i64,
+    /// Convention `performance_annualized` compounds under.
+    pub annualization_basis: AnnualizationBasis,
+    /// The elapsed days `performance_annualized` compounds over, counted on
+    /// `annualization_basis`: `actual_days_elapsed` for calendar days, else
+    /// the trading days in the same span.
+    pub annualization_days: i64,
+    /// Annualised return over a benchmark annualised across the same
+    /// `annualization_days`, as percentage points (`None` until compared; see
+    /// [`crate::utils::annualized_alpha`]).
+    pub alpha_annualized: Option<f64>,
     /// Per-stock performance breakdown.
     pub individual_performances: Vec<StockPerformance>,
     /// Tickers excluded because they lack a usable buy price or current price.
     pub excluded_tickers: Vec<String>,
+    /// Number of included stocks with a positive total return.
+    pub win_count: i32,
+    /// Number of included stocks with a negative total return. Stocks at
+    /// exactly 0.0% count as neither winners nor losers.
+    pub loss_count: i32,
+    /// Average total return of the winners, as a percentage (`None` if none).
+    pub avg_winner_return: Option<f64>,
+    /// Average total return of the losers, as a percentage (`None` if none).
+    pub avg_loser_return: Option<f64>,
+    /// Mean daily return of the equally weighted portfolio, as a percentage.
+    pub mean_daily_return_percent: f64,
+    /// Standard deviation of the equally weighted portfolio's daily returns,
+    /// as a percentage.
+    pub volatility_percent: f64,
+    /// Included stocks with fewer than two closes in the window, which
+    /// contribute zero volatility.
+    pub low_data_tickers: Vec<String>,
+    /// Largest peak-to-trough fall of the equal-weighted portfolio value over
+    /// the window, as a positive percentage of the peak (`0.0` when it never
+    /// fell below an earlier high).
+    pub max_drawdown_percent: f64,
+}
+
+/// Trading days per year, used to annualise daily risk figures.
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+impl PortfolioPerformance {
+    /// Replaces any NaN or infinite figure, here and in every stock's
+    /// [`StockPerformance`], with a finite one (see
+    /// [`crate::utils::sanitize_f64`]).
+    pub fn sanitize(&mut self) {
+        use crate::utils::sanitize_f64;
+
+        for value in [
+            &mut self.performance_90_day,
+            &mut self.performance_annualized,
+            &mut self.mean_daily_return_percent,
+            &mut self.volatility_percent,
+            &mut self.max_drawdown_percent,
+        ] {
+            *value = sanitize_f64(*value);
+        }
+        for value in [
+            &mut self.alpha_annualized,
+            &mut self.avg_winner_return,
+            &mut self.avg_loser_return,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *value = sanitize_f64(*value);
+        }
+        for stock in &mut self.individual_performances {
+            stock.sanitize();
+        }
+    }
+
+    /// Describes how `performance_annualized` was compounded, e.g.
+    /// `"over 62 trading days, 252/yr"`.
+    pub fn annualization_label(&self) -> String {
+        format!(
+            "over {} {} days, {}/yr",
+            self.annualization_days,
+            self.annualization_basis.day_kind(),
+            self.annualization_basis.days_per_year()
+        )
+    }
+
+    /// Returns the annualised Sharpe-style ratio of the portfolio's daily
+    /// returns: `(mean_daily_return - daily_risk_free) / volatility`, scaled
+    /// by `sqrt(252)`. `risk_free_rate` is an annual percentage spread evenly
+    /// over 252 trading days. `None` when the portfolio has no volatility.
+    pub fn sharpe_ratio(&self, risk_free_rate: f64) -> Option<f64> {
+        (self.volatility_percent > 0.0).then(|| {
+            let excess_daily_return =
+                self.mean_daily_return_percent - risk_free_rate / TRADING_DAYS_PER_YEAR;
+            excess_daily_return / self.volatility_percent * TRADING_DAYS_PER_YEAR.sqrt()
+        })
+    }
+
+    /// Formats the winners/losers split as a one-line summary, e.g.
+    /// `"Winners: 12 (avg +8.3%) / Losers: 7 (avg -4.1%)"`.
+    pub fn winners_losers_summary(&self) -> String {
+        let group = |label: &str, count: i32, average: Option<f64>| match average {
+            Some(average) => format!("{label}: {count} (avg {average:+.1}%)"),
+            None => format!("{label}: {count}"),
+        };
+        format!(
+            "{} / {}",
+            group("Winners", self.win_count, self.avg_winner_return),
+            group("Losers", self.loss_count, self.avg_loser_return)
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_score_accepts_fractions_and_percentages() {
+        let tsv = "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+                   intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+                   NYSE:ONE\t1\t$10.00\t\t\t\t\t\n\
+                   NYSE:FRAC\t0.95\t$10.00\t\t\t\t\t\n\
+                   NYSE:PCT\t95%\t$10.00\t\t\t\t\t\n\
+                   NYSE:SPACED\t 87.5 % \t$10.00\t\t\t\t\t\n";
+        let scores: Vec<f64> = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .from_reader(tsv.as_bytes())
+            .deserialize::<StockRecord>()
+            .map(|record| record.unwrap().score)
+            .collect();
+        assert_eq!(scores.len(), 4);
+        for (actual, expected) in scores.iter().zip([1.0, 0.95, 0.95, 0.875]) {
+            assert!((actual - expected).abs() < 1e-12, "{scores:?}");
+        }
+
+        let bad = "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+                   intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+                   NYSE:BAD\thigh\t$10.00\t\t\t\t\t\n";
+        let error = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .from_reader(bad.as_bytes())
+            .deserialize::<StockRecord>()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Failed to parse score 'high'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_position_sizing_parse() {
+        let whole = PositionSizing::parse("Buy 235 at $45.44 ~= $10,678").unwrap();
+        assert_eq!(whole.shares, 235.0);
+        assert_eq!(whole.price, 45.44);
+        assert!((whole.cost_basis() - 10_678.4).abs() < 1e-9);
+
+        let fractional = PositionSizing::parse("Buy 12.5 at $1,080.00 ~= $13,500 -$55.36").unwrap();
+        assert_eq!(fractional.shares, 12.5);
+        assert_eq!(fractional.price, 1_080.0);
+
+        assert_eq!(PositionSizing::parse("GTC: delete"), None);
+        assert_eq!(PositionSizing::parse("ADR"), None);
+        assert_eq!(PositionSizing::parse("Buy 0 at $10.00"), None);
+        assert_eq!(PositionSizing::parse(""), None);
+    }
+
+    #[test]
+    fn test_annualization_basis_from_str() {
+        assert_eq!(
+            "calendar".parse::<AnnualizationBasis>(),
+            Ok(AnnualizationBasis::Calendar)
+        );
+        assert_eq!(
+            "Trading".parse::<AnnualizationBasis>(),
+            Ok(AnnualizationBasis::Trading)
+        );
+        assert!("business".parse::<AnnualizationBasis>().is_err());
+        assert_eq!(AnnualizationBasis::default().days_per_year(), 365.25);
+        assert_eq!(AnnualizationBasis::Trading.days_per_year(), 252.0);
+    }
+
+    #[test]
+    fn test_dollar_portfolio_value_pnl() {
+        let value = DollarPortfolioValue {
+            positions: 2,
+            start_value: 2_000.0,
+            end_value: 2_150.0,
+        };
+        assert_eq!(value.pnl(), 150.0);
+        assert!((value.pnl_percent() - 7.5).abs() < 1e-9);
+
+        // $8,000 of a $10,000 portfolio sits in cash at 0%.
+        assert!((value.invested_fraction(10_000.0) - 0.2).abs() < 1e-9);
+        assert!((value.blended_return_percent(10_000.0) - 1.5).abs() < 1e-9);
+        assert_eq!(value.invested_fraction(0.0), 0.0);
+        assert_eq!(value.blended_return_percent(0.0), 0.0);
+    }
+
+    #[test]
+    fn test_winners_losers_summary() {
+        let mut performance = PortfolioPerformance {
+            score_date: "2025-01-01".to_string(),
+            total_stocks: 19,
+            performance_90_day: 3.7,
+            performance_annualized: 15.6,
+            actual_days_elapsed: 90,
+            annualization_basis: AnnualizationBasis::Calendar,
+            annualization_days: 90,
+            alpha_annualized: None,
+            individual_performances: Vec::new(),
+            excluded_tickers: Vec::new(),
+            win_count: 12,
+            loss_count: 7,
+            avg_winner_return: Some(8.3),
+            avg_loser_return: Some(-4.1),
+            mean_daily_return_percent: 0.0,
+            volatility_percent: 0.0,
+            low_data_tickers: Vec::new(),
+            max_drawdown_percent: 0.0,
+        };
+        assert_eq!(
+            performance.winners_losers_summary(),
+            "Winners: 12 (avg +8.3%) / Losers: 7 (avg -4.1%)"
+        );
+
+        performance.loss_count = 0;
+        performance.avg_loser_return = None;
+        assert_eq!(
+            performance.winners_losers_summary(),
+            "Winners: 12 (avg +8.3%) / Losers: 0"
+        );
+    }
+
+    #[test]
+    fn test_sharpe_ratio() {
+        let performance = |mean: f64, volatility: f64| PortfolioPerformance {
+            score_date: "2025-01-01".to_string(),
+            total_stocks: 2,
+            performance_90_day: 0.0,
+            performance_annualized: 0.0,
+            actual_days_elapsed: 90,
+            annualization_basis: AnnualizationBasis::Calendar,
+            annualization_days: 90,
+            alpha_annualized: None,
+            individual_performances: Vec::new(),
+            excluded_tickers: Vec::new(),
+            win_count: 0,
+            loss_count: 0,
+            avg_winner_return: None,
+            avg_loser_return: None,
+            mean_daily_return_percent: mean,
+            volatility_percent: volatility,
+            low_data_tickers: Vec::new(),
+            max_drawdown_percent: 0.0,
+        };
+
+        let sharpe = performance(0.1, 1.0).sharpe_ratio(0.0).unwrap();
+        assert!((sharpe - 0.1 * 252f64.sqrt()).abs() < 1e-12);
+
+        // 25.2% a year is 0.1% a day, cancelling the whole excess return.
+        assert!(performance(0.1, 1.0).sharpe_ratio(25.2).unwrap().abs() < 1e-12);
+        assert_eq!(performance(0.1, 0.0).sharpe_ratio(0.0), None);
+    }
+
     #[test]
     fn test_stock_record_new() {
         let record = StockRecord::new("AAPL".to_string(), 0.95, 150.0);
@@ -369,6 +1274,40 @@ mod tests {
         assert!((deserialized.intrinsic_value_per_share_adjusted.unwrap() - 28.69).abs() < 0.01);
     }
 
+    #[test]
+    fn test_currency_format_inserts_thousands_separators() {
+        let format = CurrencyFormat::default();
+        assert_eq!(format.format(3208.46), "$3,208.46");
+        assert_eq!(format.format(-45749.7), "-$45,749.70");
+        assert_eq!(format.format(1_234_567.891), "$1,234,567.89");
+        assert_eq!(format.format(999.999), "$1,000.00");
+        assert_eq!(format.format(18.42), "$18.42");
+        assert_eq!(format.format(-0.001), "$0.00");
+
+        let whole_dollars = CurrencyFormat {
+            precision: 0,
+            thousands_separator: false,
+        };
+        assert_eq!(whole_dollars.format(3208.46), "$3208");
+        assert_eq!(whole_dollars.format(-1414.96), "-$1415");
+    }
+
+    #[test]
+    fn test_currency_serialization_round_trips() {
+        for value in [3208.46, -45749.7, 1_234_567.89, 0.0, 18.42] {
+            let mut json = Vec::new();
+            serialize_currency(&value, &mut serde_json::Serializer::new(&mut json)).unwrap();
+            let json = String::from_utf8(json).unwrap();
+            let parsed =
+                deserialize_currency(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+            assert!((parsed - value).abs() < 1e-9, "{json}: {parsed} != {value}");
+        }
+
+        let mut json = Vec::new();
+        serialize_currency(&3208.46, &mut serde_json::Serializer::new(&mut json)).unwrap();
+        assert_eq!(String::from_utf8(json).unwrap(), "\"$3,208.46\"");
+    }
+
     #[test]
     fn test_currency_deserialization_with_negative_values() {
         // Test that negative currency values with formatting are parsed correctly
@@ -381,6 +1320,8 @@ mod tests {
             ("$27.56", 27.56),
             ("$3,208.46", 3208.46),
             ("$3,427.71", 3427.71),
+            ("", 0.0),
+            ("$0.00", 0.0),
         ];
 
         for (input, expected) in test_cases {
@@ -412,6 +1353,9 @@ mod tests {
             performance_90_day: None,
             performance_annualized: None,
             total_stocks: None,
+            return_30d: None,
+            return_60d: None,
+            return_90d: None,
         };
 
         assert_eq!(entry.date, "2025-06-20");
@@ -429,6 +1373,9 @@ mod tests {
             performance_90_day: None,
             performance_annualized: None,
             total_stocks: None,
+            return_30d: None,
+            return_60d: None,
+            return_90d: None,
         };
 
         let entry2 = ScoreEntry {
@@ -440,14 +1387,36 @@ mod tests {
             performance_90_day: None,
             performance_annualized: None,
             total_stocks: None,
+            return_30d: None,
+            return_60d: None,
+            return_90d: None,
         };
 
         let index_data = IndexData {
             scores: vec![entry1, entry2],
+            ..IndexData::default()
         };
 
         assert_eq!(index_data.scores.len(), 2);
         assert_eq!(index_data.scores[0].date, "2025-06-20");
         assert_eq!(index_data.scores[1].date, "2025-06-21");
     }
+
+    #[test]
+    fn test_index_data_generation_stamp_is_optional() {
+        let legacy: IndexData = serde_json::from_str(r#"{"scores": []}"#).unwrap();
+        assert_eq!(legacy.generated_at, None);
+        assert_eq!(legacy.generator_version, None);
+        assert_eq!(serde_json::to_string(&legacy).unwrap(), r#"{"scores":[]}"#);
+
+        let mut stamped = legacy;
+        stamped.stamp_generation();
+        let json = serde_json::to_string(&stamped).unwrap();
+        let reread: IndexData = serde_json::from_str(&json).unwrap();
+        assert_eq!(reread.generated_at, stamped.generated_at);
+        assert_eq!(
+            reread.generator_version.as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+    }
 }
@@ -0,0 +1,201 @@
+use crate::error::Result;
+use crate::models::IndexData;
+use std::fmt::Write;
+
+/// Width of the inline SVG chart, in pixels.
+const CHART_WIDTH: f64 = 800.0;
+/// Height of the inline SVG chart, in pixels.
+const CHART_HEIGHT: f64 = 240.0;
+
+/// Escapes the characters that are significant in HTML text and attributes.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Formats an optional percentage for a table cell, or an em dash when absent.
+fn format_percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "&mdash;".to_string(), |v| format!("{v:.2}%"))
+}
+
+/// Renders an inline SVG bar chart of the annualised return of every entry
+/// that has one, oldest first. Positive bars rise from the zero line in green,
+/// negative bars fall from it in red.
+fn render_annualized_chart(index_data: &IndexData) -> String {
+    let mut points: Vec<(&str, f64)> = index_data
+        .scores
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .performance_annualized
+                .filter(|value| value.is_finite())
+                .map(|value| (entry.date.as_str(), value))
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.cmp(b.0));
+
+    if points.is_empty() {
+        return "<p>No annualised returns to chart yet.</p>\n".to_string();
+    }
+
+    let max_positive = points.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+    let max_negative = points.iter().map(|(_, v)| -*v).fold(0.0, f64::max);
+    let range = (max_positive + max_negative).max(f64::EPSILON);
+    let zero_y = CHART_HEIGHT * max_positive / range;
+    let slot = CHART_WIDTH / points.len() as f64;
+    let bar_width = (slot * 0.8).max(1.0);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{CHART_WIDTH}\" \
+         height=\"{CHART_HEIGHT}\" viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\" \
+         role=\"img\" aria-label=\"Annualized return by score date\">\n"
+    );
+    for (i, (date, value)) in points.iter().enumerate() {
+        let height = CHART_HEIGHT * value.abs() / range;
+        let y = if *value >= 0.0 {
+            zero_y - height
+        } else {
+            zero_y
+        };
+        let x = i as f64 * slot + (slot - bar_width) / 2.0;
+        let fill = if *value >= 0.0 { "#2e7d32" } else { "#c62828" };
+        let _ = writeln!(
+            svg,
+            "  <rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{bar_width:.1}\" height=\"{height:.1}\" \
+             fill=\"{fill}\"><title>{}: {value:.2}%</title></rect>",
+            escape_html(date)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "  <line x1=\"0\" y1=\"{zero_y:.1}\" x2=\"{CHART_WIDTH}\" y2=\"{zero_y:.1}\" \
+         stroke=\"#555\" stroke-width=\"1\"/>"
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Renders a self-contained HTML performance summary of `index_data`: a table
+/// of every score date with its performance columns and an inline SVG bar chart
+/// of annualised returns. All styling is inline; the page loads no external
+/// JavaScript or CSS.
+pub fn render_html_report(index_data: &IndexData) -> String {
+    let mut entries: Vec<_> = index_data.scores.iter().collect();
+    entries.sort_by(|a, b| b.date.cmp(&a.date));
+
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>GRQ Validation Performance Report</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; color: #222; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 10px; text-align: right; }\n\
+         th:first-child, td:first-child { text-align: left; }\n\
+         .neg { color: #c62828; }\n\
+         </style>\n</head>\n<body>\n<h1>GRQ Validation Performance Report</h1>\n\
+         <h2>Annualized Return by Score Date</h2>\n",
+    );
+    html.push_str(&render_annualized_chart(index_data));
+
+    html.push_str(
+        "<h2>Performance by Score Date</h2>\n<table>\n<thead><tr><th>Date</th>\
+         <th>Stocks</th><th>90-Day</th><th>Annualized</th><th>30d</th><th>60d</th>\
+         <th>90d</th></tr></thead>\n<tbody>\n",
+    );
+    for entry in entries {
+        let class = if entry.performance_90_day.is_some_and(|v| v < 0.0) {
+            " class=\"neg\""
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            html,
+            "<tr{class}><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            escape_html(&entry.date),
+            entry
+                .total_stocks
+                .map_or_else(|| "&mdash;".to_string(), |n| n.to_string()),
+            format_percent(entry.performance_90_day),
+            format_percent(entry.performance_annualized),
+            format_percent(entry.return_30d),
+            format_percent(entry.return_60d),
+            format_percent(entry.return_90d),
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Writes the HTML report rendered by [`render_html_report`] to `output_path`.
+///
+/// # Errors
+///
+/// Returns an error if the report file cannot be written.
+pub fn generate_html_report(index_data: &IndexData, output_path: &str) -> Result<()> {
+    std::fs::write(output_path, render_html_report(index_data))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ScoreEntry;
+
+    fn entry(date: &str, annualized: Option<f64>) -> ScoreEntry {
+        ScoreEntry {
+            year: date[..4].to_string(),
+            month: "June".to_string(),
+            day: date[8..].to_string(),
+            file: format!("{date}.tsv"),
+            date: date.to_string(),
+            performance_90_day: annualized.map(|a| a / 4.0),
+            performance_annualized: annualized,
+            total_stocks: annualized.map(|_| 10),
+            return_30d: None,
+            return_60d: None,
+            return_90d: None,
+        }
+    }
+
+    #[test]
+    fn test_render_html_report_is_self_contained() {
+        let index_data = IndexData {
+            scores: vec![
+                entry("2025-06-20", Some(24.0)),
+                entry("2025-06-21", Some(-12.0)),
+                entry("2025-06-22", None),
+            ],
+            ..IndexData::default()
+        };
+
+        let html = render_html_report(&index_data);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("<link"));
+        // One bar per entry with an annualised figure; the missing one is
+        // listed in the table but not charted.
+        assert_eq!(html.matches("<rect").count(), 2);
+        assert!(html.contains("<td>2025-06-22</td><td>&mdash;</td>"));
+        assert!(html.contains("<td>24.00%</td>"));
+        assert!(html.contains("<tr class=\"neg\"><td>2025-06-21</td>"));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
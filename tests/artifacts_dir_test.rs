@@ -0,0 +1,92 @@
+//! With an artifacts directory set, the CSVs and detail JSON generated for a
+//! score file are written under it, mirroring `scores/YYYY/Month/`, and read
+//! back from there, leaving the docs tree untouched.
+
+use grq_validation::utils::{
+    calculate_portfolio_performance_with_options, create_dividend_csv_for_score_file_with_options,
+    derive_csv_output_path_with_options, write_performance_detail, CsvKind, DataOptions,
+    PerformanceOptions,
+};
+use std::fs;
+use std::path::Path;
+
+/// Every file under `dir`, relative to it, sorted.
+fn files_under(dir: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(current).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                let relative = path.strip_prefix(dir).unwrap();
+                files.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[test]
+fn generated_files_go_to_the_artifacts_dir() {
+    let docs = tempfile::tempdir().unwrap();
+    let month = docs.path().join("scores").join("2025").join("January");
+    fs::create_dir_all(&month).unwrap();
+    let score_path = month.join("15.tsv");
+    fs::write(
+        &score_path,
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t$150.00\t\t\t\t\t\n",
+    )
+    .unwrap();
+    let score_path = score_path.to_string_lossy().into_owned();
+
+    let dividends = tempfile::tempdir().unwrap();
+    let artifacts = tempfile::tempdir().unwrap();
+    let options = PerformanceOptions {
+        data: DataOptions {
+            dividend_data_path: Some(dividends.path().to_path_buf()),
+            artifacts_dir: Some(artifacts.path().to_path_buf()),
+            ..DataOptions::default()
+        },
+        ..PerformanceOptions::default()
+    };
+
+    // Stand in for a market-data archive by writing the derived CSV directly.
+    let market_csv =
+        derive_csv_output_path_with_options(&score_path, CsvKind::Market, &options.data);
+    assert!(Path::new(&market_csv).starts_with(artifacts.path()));
+    fs::create_dir_all(Path::new(&market_csv).parent().unwrap()).unwrap();
+    fs::write(
+        &market_csv,
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:TEST,0,0,0,100.0\n\
+         2025-04-15,NYSE:TEST,0,0,0,110.0\n",
+    )
+    .unwrap();
+
+    create_dividend_csv_for_score_file_with_options(
+        &score_path,
+        &["NYSE:TEST".to_string()],
+        "2025-01-15",
+        &options.data,
+    )
+    .unwrap();
+    let performance =
+        calculate_portfolio_performance_with_options(&score_path, "2025-01-15", &options).unwrap();
+    assert!((performance.performance_90_day - 10.0).abs() < 1e-9);
+    write_performance_detail(&score_path, &performance, &options.data).unwrap();
+
+    assert_eq!(files_under(docs.path()), ["scores/2025/January/15.tsv"]);
+    assert_eq!(
+        files_under(artifacts.path()),
+        [
+            "scores/2025/January/15-dividends.csv",
+            "scores/2025/January/15-performance.json",
+            "scores/2025/January/15.csv",
+        ]
+    );
+}
@@ -0,0 +1,127 @@
+//! End-to-end tests of the default batch run over a temporary docs tree and
+//! share-price archive.
+
+mod common;
+
+use chrono::{Duration, NaiveDate, Utc};
+use std::path::Path;
+use std::process::Command;
+
+/// Writes `TEST`'s market-data file with one day on `date`, traded at
+/// `volume`.
+fn write_prices(base: &Path, date: NaiveDate, volume: &str) {
+    let day = date.format("%Y-%m-%d").to_string();
+    common::write_prices_with_volume(base, "TEST", &day, &[(&day, "10.00")], volume);
+}
+
+/// Writes a score file holding `NYSE:TEST` for each of `dates`, and an
+/// `index.json` listing them, under `docs/scores`.
+fn write_docs(docs: &Path, dates: &[NaiveDate]) {
+    let scores = docs.join("scores");
+    let mut entries = Vec::new();
+    for date in dates {
+        let file = date.format("%Y/%B/%-d.tsv").to_string();
+        let path = scores.join(&file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+             intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+             NYSE:TEST\t1.0\t12.00\t\t\t\t\t\n",
+        )
+        .unwrap();
+        entries.push(serde_json::json!({
+            "year": date.format("%Y").to_string(),
+            "month": date.format("%B").to_string(),
+            "day": date.format("%-d").to_string(),
+            "file": file,
+            "date": date.format("%Y-%m-%d").to_string(),
+        }));
+    }
+    std::fs::write(
+        scores.join("index.json"),
+        serde_json::json!({ "scores": entries }).to_string(),
+    )
+    .unwrap();
+}
+
+/// Runs a batch over `docs` with the archive at `prices` and no dividend data.
+fn run_batch(docs: &Path, prices: &Path, extra: &[&str]) -> std::process::Output {
+    let no_dividends = tempfile::tempdir().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_grq-validation"))
+        .args([
+            "--docs-path",
+            docs.to_str().unwrap(),
+            "--market-data-path",
+            prices.to_str().unwrap(),
+            "--dividend-data-path",
+            no_dividends.path().to_str().unwrap(),
+            "--no-cache",
+        ])
+        .args(extra)
+        .output()
+        .expect("run grq-validation binary")
+}
+
+/// The date `days` before today (UTC).
+fn days_ago(days: i64) -> NaiveDate {
+    Utc::now().date_naive() - Duration::days(days)
+}
+
+#[test]
+fn verify_passes_well_formed_csvs() {
+    let docs = tempfile::tempdir().unwrap();
+    let prices = tempfile::tempdir().unwrap();
+    let date = days_ago(10);
+    write_docs(docs.path(), &[date]);
+    write_prices(prices.path(), date, "1000");
+
+    let output = run_batch(docs.path(), prices.path(), &["--verify"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+}
+
+#[test]
+fn verify_fails_the_run_on_a_malformed_csv() {
+    // The volume is copied into the market-data CSV as-is.
+    let docs = tempfile::tempdir().unwrap();
+    let prices = tempfile::tempdir().unwrap();
+    let date = days_ago(10);
+    write_docs(docs.path(), &[date]);
+    write_prices(prices.path(), date, "n/a");
+
+    let output = run_batch(docs.path(), prices.path(), &["--verify"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "stderr: {stderr}");
+    assert!(stderr.contains("failed verification"), "stderr: {stderr}");
+    assert!(
+        stderr.contains("non-numeric volume 'n/a'"),
+        "stderr: {stderr}"
+    );
+
+    // Without --verify the same run succeeds.
+    let output = run_batch(docs.path(), prices.path(), &[]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn limit_processes_only_the_first_n_score_files() {
+    let docs = tempfile::tempdir().unwrap();
+    let prices = tempfile::tempdir().unwrap();
+    let dates = [days_ago(30), days_ago(20), days_ago(10)];
+    write_docs(docs.path(), &dates);
+    write_prices(prices.path(), dates[0], "1000");
+
+    let output = run_batch(docs.path(), prices.path(), &["--limit", "2"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+
+    // Only the processed score files get a market-data CSV beside them.
+    let has_csv = |date: &NaiveDate| {
+        docs.path()
+            .join("scores")
+            .join(date.format("%Y/%B/%-d.csv").to_string())
+            .exists()
+    };
+    assert_eq!(dates.map(|date| has_csv(&date)), [true, true, false]);
+}
@@ -7,6 +7,7 @@
 //!
 //! These tests exercise the real binary end-to-end and assert on observable
 //! behaviour (accepted/rejected flags and exit codes), not implementation.
+//! They also cover flag values clap must reject before any work starts.
 
 use std::process::Command;
 
@@ -57,3 +58,19 @@ fn calculate_performance_flag_is_still_accepted() {
         "unreachable calculate-performance block should be gone, stderr: {stderr}"
     );
 }
+
+#[test]
+fn negative_max_buy_gap_days_is_rejected() {
+    // A negative gap would make the buy window end before the score date.
+    let output = run_with_args(&["--max-buy-gap-days=-1"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        !output.status.success(),
+        "expected non-zero exit for a negative gap, stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("--max-buy-gap-days"),
+        "expected the error to name the flag, stderr: {stderr}"
+    );
+}
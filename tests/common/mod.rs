@@ -0,0 +1,65 @@
+//! Fixtures shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+/// Writes `symbol`'s market-data file into the archive at `base`, last
+/// refreshed on `refreshed`, with one day per `(date, close)` traded at a
+/// volume of 1000. Returns the file's path.
+pub fn write_prices(
+    base: &Path,
+    symbol: &str,
+    refreshed: &str,
+    closes: &[(&str, &str)],
+) -> PathBuf {
+    write_prices_with_volume(base, symbol, refreshed, closes, "1000")
+}
+
+/// Writes `symbol`'s market-data file as [`write_prices`] does, with every day
+/// traded at `volume`.
+pub fn write_prices_with_volume(
+    base: &Path,
+    symbol: &str,
+    refreshed: &str,
+    closes: &[(&str, &str)],
+    volume: &str,
+) -> PathBuf {
+    let series: serde_json::Map<String, serde_json::Value> = closes
+        .iter()
+        .map(|(date, close)| {
+            (
+                (*date).to_string(),
+                serde_json::json!({
+                    "1. open": close,
+                    "2. high": close,
+                    "3. low": close,
+                    "4. close": close,
+                    "5. adjusted close": close,
+                    "6. volume": volume,
+                    "7. dividend amount": "0.0000",
+                    "8. split coefficient": "1.0",
+                }),
+            )
+        })
+        .collect();
+    let dir = base.join("data").join(&symbol[..1]);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("{symbol}.json"));
+    std::fs::write(
+        &path,
+        serde_json::json!({
+            "Meta Data": {
+                "1. Information": "Daily Prices (fixture)",
+                "2. Symbol": symbol,
+                "3. Last Refreshed": refreshed,
+                "4. Output Size": "Full size",
+                "5. Time Zone": "US/Eastern",
+            },
+            "Time Series (Daily)": series,
+        })
+        .to_string(),
+    )
+    .unwrap();
+    path
+}
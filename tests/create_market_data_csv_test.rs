@@ -3,7 +3,7 @@
 //!
 //! These were previously the only market-data writers with no test exercising
 //! them, directly or indirectly. Rather than depend on the external
-//! `MARKET_DATA_BASE_PATH` data repository (which is absent in CI and makes the
+//! market-data repository (which is absent in CI and makes the
 //! sibling long-format test skip), each test drops a small, fully controlled
 //! market-data fixture at the location the function reads from, asserts the
 //! observable CSV output, then removes the fixture again. The assertions pin
@@ -12,12 +12,12 @@
 
 use anyhow::Result;
 use grq_validation::utils::{
-    create_market_data_csv, create_market_data_csv_for_score_file, MARKET_DATA_BASE_PATH,
+    create_market_data_csv, create_market_data_csv_for_score_file, DataOptions,
 };
 use std::path::{Path, PathBuf};
 
 /// Clearly-synthetic symbols so a fixture can never collide with a real symbol
-/// in an existing `MARKET_DATA_BASE_PATH` data repository. Each test uses a
+/// in an existing market-data repository. Each test uses a
 /// distinct symbol so the fixtures live at distinct paths and never race when
 /// the test harness runs them in parallel (one test's `Drop` must not delete a
 /// fixture another test is still reading).
@@ -29,8 +29,9 @@ const FIXTURE_SYMBOL_WRAPPER: &str = "GRQVTEST265B";
 const SCORE_DATE: &str = "2025-04-15";
 
 /// RAII guard that installs a market-data fixture for a given symbol under
-/// `MARKET_DATA_BASE_PATH` and removes exactly what it created on drop, so the
-/// test leaves no trace whether or not the external data repository pre-exists.
+/// `DataOptions::market_data_base_path` and removes exactly what it created on
+/// drop, so the test leaves no trace whether or not the external data
+/// repository pre-exists.
 struct MarketDataFixture {
     json_path: PathBuf,
     /// The outermost directory this guard created (and must remove on drop), or
@@ -43,7 +44,7 @@ impl MarketDataFixture {
     /// the 180-day window, one row before it, and one row after it, so a test
     /// can assert both inclusion and exclusion.
     fn install(symbol: &str) -> Result<Self> {
-        let base = Path::new(MARKET_DATA_BASE_PATH);
+        let base = DataOptions::default().market_data_base_path();
         let first_letter = symbol.chars().next().unwrap().to_string();
         let symbol_dir = base.join("data").join(&first_letter);
 
@@ -3,19 +3,20 @@
 //! The long-format market-data writer previously had no unconditional test:
 //! its only test-adjacent reference (`create_market_data_long_csv_for_score_file`
 //! in `tests/market_data_tests.rs`) early-returns unless an external
-//! `MARKET_DATA_BASE_PATH` repository exists, so on CI and most machines it
+//! market-data repository exists, so on CI and most machines it
 //! never runs. These tests drop a small, fully controlled market-data fixture
 //! at the location the function reads from and assert the observable contract —
-//! the 8-column `date,ticker,high,low,open,close,split_coefficient,volume`
+//! the 9-column
+//! `date,ticker,high,low,open,close,split_coefficient,volume,adjusted_close`
 //! output and the "no rows written → error" guard — without caring how the
 //! writer is implemented. They mirror `tests/create_market_data_csv_test.rs`.
 
 use anyhow::Result;
-use grq_validation::utils::{create_market_data_long_csv, MARKET_DATA_BASE_PATH};
+use grq_validation::utils::{create_market_data_long_csv, DataOptions};
 use std::path::{Path, PathBuf};
 
 /// Clearly-synthetic symbol so a fixture can never collide with a real symbol
-/// in an existing `MARKET_DATA_BASE_PATH` data repository.
+/// in an existing market-data repository.
 ///
 /// Each fixture-installing test uses a *distinct* symbol so their fixture files
 /// never share a path: cargo runs tests in parallel by default, and a shared
@@ -35,13 +36,20 @@ const FIXTURE_SYMBOL_REPLACE: &str = "GRQVTEST634B";
 /// Full ticker code for the replacement test's fixture symbol.
 const FIXTURE_TICKER_REPLACE: &str = "NYSE:GRQVTEST634B";
 
+/// Distinct fixture symbol for the split-adjustment test.
+const FIXTURE_SYMBOL_SPLIT: &str = "GRQVTEST634C";
+
+/// Full ticker code for the split-adjustment test's fixture symbol.
+const FIXTURE_TICKER_SPLIT: &str = "NYSE:GRQVTEST634C";
+
 /// Score-file date used by the happy-path test; the 180-day window therefore
 /// runs from `2025-04-15` to `2025-10-12` inclusive.
 const SCORE_DATE: &str = "2025-04-15";
 
 /// RAII guard that installs a market-data fixture for a given symbol under
-/// `MARKET_DATA_BASE_PATH` and removes exactly what it created on drop, so the
-/// test leaves no trace whether or not the external data repository pre-exists.
+/// `DataOptions::market_data_base_path` and removes exactly what it created on
+/// drop, so the test leaves no trace whether or not the external data
+/// repository pre-exists.
 struct MarketDataFixture {
     json_path: PathBuf,
     /// The outermost directory this guard created (and must remove on drop), or
@@ -54,7 +62,12 @@ impl MarketDataFixture {
     /// the 180-day window, one row before it, and one row after it, so a test
     /// can assert both inclusion and exclusion.
     fn install(symbol: &str) -> Result<Self> {
-        let base = Path::new(MARKET_DATA_BASE_PATH);
+        Self::install_json(symbol, &fixture_json(symbol))
+    }
+
+    /// Writes `json` as the market-data file for `symbol`.
+    fn install_json(symbol: &str, json: &str) -> Result<Self> {
+        let base = DataOptions::default().market_data_base_path();
         let first_letter = symbol.chars().next().unwrap().to_string();
         let symbol_dir = base.join("data").join(&first_letter);
 
@@ -62,7 +75,7 @@ impl MarketDataFixture {
         std::fs::create_dir_all(&symbol_dir)?;
 
         let json_path = symbol_dir.join(format!("{symbol}.json"));
-        std::fs::write(&json_path, fixture_json(symbol))?;
+        std::fs::write(&json_path, json)?;
 
         Ok(Self {
             json_path,
@@ -157,7 +170,7 @@ fn fixture_json(symbol: &str) -> String {
 }
 
 #[test]
-fn create_market_data_long_csv_writes_eight_column_rows() -> Result<()> {
+fn create_market_data_long_csv_writes_nine_column_rows() -> Result<()> {
     let _fixture = MarketDataFixture::install(FIXTURE_SYMBOL)?;
 
     let out_dir = tempfile::tempdir()?;
@@ -168,10 +181,10 @@ fn create_market_data_long_csv_writes_eight_column_rows() -> Result<()> {
 
     let csv = std::fs::read_to_string(&out_path)?;
 
-    // 8-column header contract.
+    // 9-column header contract.
     assert_eq!(
         csv.lines().next().unwrap(),
-        "date,ticker,high,low,open,close,split_coefficient,volume",
+        "date,ticker,high,low,open,close,split_coefficient,volume,adjusted_close",
         "unexpected long-format CSV header in:\n{csv}"
     );
 
@@ -179,7 +192,7 @@ fn create_market_data_long_csv_writes_eight_column_rows() -> Result<()> {
     // column keeps the full code (exchange prefix included).
     assert!(
         csv.contains(&format!(
-            "2025-04-15,{FIXTURE_TICKER},105.25,98.75,100.5,102.0,1.0,123456"
+            "2025-04-15,{FIXTURE_TICKER},105.25,98.75,100.5,102.0,1.0,123456,102.0000"
         )),
         "expected the fully-mapped window-start row in:\n{csv}"
     );
@@ -197,6 +210,70 @@ fn create_market_data_long_csv_writes_eight_column_rows() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn create_market_data_long_csv_adjusts_closes_for_a_split() -> Result<()> {
+    // A 2-for-1 split on 2025-05-01: the raw close halves overnight, but the
+    // adjusted close of every earlier day is halved too, so the series is
+    // continuous.
+    let day = |close: &str, split: &str| {
+        serde_json::json!({
+            "1. open": close,
+            "2. high": close,
+            "3. low": close,
+            "4. close": close,
+            "5. adjusted close": close,
+            "6. volume": "1000",
+            "7. dividend amount": "0.0",
+            "8. split coefficient": split,
+        })
+    };
+    let json = serde_json::json!({
+        "Meta Data": {
+            "1. Information": "Daily Prices (fixture)",
+            "2. Symbol": FIXTURE_SYMBOL_SPLIT,
+            "3. Last Refreshed": "2025-10-12",
+            "4. Output Size": "Full size",
+            "5. Time Zone": "US/Eastern",
+        },
+        "Time Series (Daily)": {
+            "2025-04-15": day("100.0", "1.0"),
+            "2025-04-30": day("104.0", "1.0"),
+            "2025-05-01": day("52.5", "2.0"),
+            "2025-05-02": day("53.0", "1.0"),
+        },
+    })
+    .to_string();
+    let _fixture = MarketDataFixture::install_json(FIXTURE_SYMBOL_SPLIT, &json)?;
+
+    let out_dir = tempfile::tempdir()?;
+    let out_path = out_dir.path().join("split.csv");
+    let out = out_path.to_str().expect("temp path is valid UTF-8");
+
+    create_market_data_long_csv(&[FIXTURE_TICKER_SPLIT.to_string()], SCORE_DATE, out)?;
+
+    let csv = std::fs::read_to_string(&out_path)?;
+    let adjusted: Vec<(&str, &str, &str)> = csv
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            (fields[0], fields[5], fields[8])
+        })
+        .collect();
+    assert_eq!(
+        adjusted,
+        vec![
+            ("2025-04-15", "100.0", "50.0000"),
+            ("2025-04-30", "104.0", "52.0000"),
+            ("2025-05-01", "52.5", "52.5000"),
+            ("2025-05-02", "53.0", "53.0000"),
+        ],
+        "unexpected adjusted closes in:\n{csv}"
+    );
+
+    Ok(())
+}
+
 #[test]
 fn create_market_data_long_csv_errors_when_all_tickers_skipped() -> Result<()> {
     // No fixture installed: the symbol has no market-data file, so the only
@@ -280,7 +357,7 @@ fn create_market_data_long_csv_replaces_existing_when_fresh_data_available() ->
     let csv = std::fs::read_to_string(&out_path)?;
     assert_eq!(
         csv.lines().next().unwrap(),
-        "date,ticker,high,low,open,close,split_coefficient,volume",
+        "date,ticker,high,low,open,close,split_coefficient,volume,adjusted_close",
         "unexpected header after replacement in:\n{csv}"
     );
     assert!(
@@ -298,3 +375,69 @@ fn create_market_data_long_csv_replaces_existing_when_fresh_data_available() ->
 
     Ok(())
 }
+
+/// Distinct fixture symbols for the row-ordering test.
+const FIXTURE_SYMBOL_ORDER_A: &str = "GRQVTEST634D";
+const FIXTURE_SYMBOL_ORDER_B: &str = "GRQVTEST634E";
+
+#[test]
+fn create_market_data_long_csv_sorts_rows_by_date_then_ticker() -> Result<()> {
+    let two_days = |symbol: &str| {
+        let day = |close: &str| {
+            serde_json::json!({
+                "1. open": close, "2. high": close, "3. low": close, "4. close": close,
+                "5. adjusted close": close, "6. volume": "1000",
+                "7. dividend amount": "0.0", "8. split coefficient": "1.0",
+            })
+        };
+        serde_json::json!({
+            "Meta Data": {
+                "1. Information": "Daily Prices (fixture)",
+                "2. Symbol": symbol,
+                "3. Last Refreshed": "2025-10-12",
+                "4. Output Size": "Full size",
+                "5. Time Zone": "US/Eastern",
+            },
+            "Time Series (Daily)": {
+                "2025-04-16": day("11.0"),
+                "2025-04-15": day("10.0"),
+            },
+        })
+        .to_string()
+    };
+    let _a =
+        MarketDataFixture::install_json(FIXTURE_SYMBOL_ORDER_A, &two_days(FIXTURE_SYMBOL_ORDER_A))?;
+    let _b =
+        MarketDataFixture::install_json(FIXTURE_SYMBOL_ORDER_B, &two_days(FIXTURE_SYMBOL_ORDER_B))?;
+
+    let out_dir = tempfile::tempdir()?;
+    let out_path = out_dir.path().join("long.csv");
+    let out = out_path.to_str().expect("temp path is valid UTF-8");
+
+    // Tickers deliberately out of order: the output must not follow them.
+    let ticker_a = format!("NYSE:{FIXTURE_SYMBOL_ORDER_A}");
+    let ticker_b = format!("NYSE:{FIXTURE_SYMBOL_ORDER_B}");
+    create_market_data_long_csv(&[ticker_b.clone(), ticker_a.clone()], SCORE_DATE, out)?;
+
+    let csv = std::fs::read_to_string(&out_path)?;
+    let keys: Vec<(&str, &str)> = csv
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let mut columns = line.split(',');
+            (columns.next().unwrap(), columns.next().unwrap())
+        })
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            ("2025-04-15", ticker_a.as_str()),
+            ("2025-04-15", ticker_b.as_str()),
+            ("2025-04-16", ticker_a.as_str()),
+            ("2025-04-16", ticker_b.as_str()),
+        ],
+        "rows must be sorted by (date, ticker) in:\n{csv}"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Per-dividend line items on `StockPerformance::dividends`.
+
+use chrono::NaiveDate;
+use grq_validation::utils::{
+    calculate_portfolio_performance_with_options, DataOptions, PerformanceOptions,
+};
+use std::fs;
+
+#[test]
+fn test_stock_performance_lists_each_dividend() {
+    let dividends = tempfile::tempdir().unwrap();
+    let symbol_dir = dividends.path().join("data").join("D");
+    fs::create_dir_all(&symbol_dir).unwrap();
+    // Two dividends inside the 90-day window, one before it and one after.
+    fs::write(
+        symbol_dir.join("DIVI.json"),
+        r#"{"symbol": "DIVI", "data": [
+            {"ex_dividend_date": "2025-03-01", "declaration_date": null,
+             "record_date": null, "payment_date": null, "amount": "0.50"},
+            {"ex_dividend_date": "2025-02-15", "declaration_date": null,
+             "record_date": null, "payment_date": null, "amount": "0.09"},
+            {"ex_dividend_date": "2024-12-15", "declaration_date": null,
+             "record_date": null, "payment_date": null, "amount": "0.08"},
+            {"ex_dividend_date": "2025-05-15", "declaration_date": null,
+             "record_date": null, "payment_date": null, "amount": "0.10"}
+        ]}"#,
+    )
+    .unwrap();
+    let options = PerformanceOptions {
+        data: DataOptions {
+            dividend_data_path: Some(dividends.path().to_path_buf()),
+            ..DataOptions::default()
+        },
+        ..PerformanceOptions::default()
+    };
+
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("15.tsv");
+    fs::write(
+        &score_path,
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:DIVI\t1.0\t$120.00\t\t\t\t\t\n",
+    )
+    .unwrap();
+    fs::write(
+        docs.path().join("15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:DIVI,0,0,0,100.0\n\
+         2025-04-15,NYSE:DIVI,0,0,0,110.0\n",
+    )
+    .unwrap();
+
+    let performance = calculate_portfolio_performance_with_options(
+        &score_path.to_string_lossy(),
+        "2025-01-15",
+        &options,
+    )
+    .unwrap();
+    let stock = &performance.individual_performances[0];
+    let date = |value: &str| NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap();
+    assert_eq!(
+        stock.dividends,
+        vec![(date("2025-02-15"), 0.09), (date("2025-03-01"), 0.50)]
+    );
+    let total: f64 = stock.dividends.iter().map(|(_, amount)| amount).sum();
+    assert!((stock.dividends_total - total).abs() < 1e-12);
+}
@@ -1,13 +1,12 @@
 use anyhow::Result;
 use grq_validation::utils::{
-    create_dividend_csv_for_score_file, extract_ticker_codes_from_score_file,
-    DIVIDEND_DATA_BASE_PATH,
+    create_dividend_csv_for_score_file, extract_ticker_codes_from_score_file, DataOptions,
 };
 
 #[test]
 fn test_create_dividend_csv_for_first_score_file() -> Result<()> {
     // Skip test if external data repository is not available
-    if !std::path::Path::new(DIVIDEND_DATA_BASE_PATH).exists() {
+    if !DataOptions::default().dividend_data_base_path().exists() {
         println!("Skipping test_create_dividend_csv_for_first_score_file: external data repository not available");
         return Ok(());
     }
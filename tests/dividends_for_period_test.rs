@@ -0,0 +1,119 @@
+//! `calculate_dividends_for_period` sums only the dividends inside its range,
+//! and `create_dividend_csv` writes them in a stable order.
+
+use grq_validation::models::{DividendData, DividendRecord};
+use grq_validation::utils::{
+    calculate_dividends_for_period, create_dividend_csv_with_options, DataOptions,
+    PerformanceOptions,
+};
+use std::sync::OnceLock;
+
+fn record(ex_dividend_date: &str, amount: &str) -> DividendRecord {
+    DividendRecord {
+        ex_dividend_date: ex_dividend_date.to_string(),
+        declaration_date: None,
+        record_date: None,
+        payment_date: None,
+        amount: amount.to_string(),
+        currency: None,
+    }
+}
+
+/// Sets up the shared archive once: `PAYER` has quarterly dividends from
+/// 2024-12-31 to 2025-07-01 and `PAYEE` pays on two of the same dates; no
+/// other ticker has a dividend file. Returns data options reading it.
+fn dividend_archive() -> &'static DataOptions {
+    static ARCHIVE: OnceLock<(tempfile::TempDir, DataOptions)> = OnceLock::new();
+    &ARCHIVE
+        .get_or_init(|| {
+            let archive = tempfile::tempdir().unwrap();
+            let dir = archive.path().join("data").join("P");
+            std::fs::create_dir_all(&dir).unwrap();
+            for dividends in [
+                DividendData {
+                    symbol: "PAYER".to_string(),
+                    data: vec![
+                        record("2024-12-31", "0.10"),
+                        record("2025-01-02", "0.20"),
+                        record("2025-02-14", "0.30"),
+                        record("2025-04-02", "0.40"),
+                        record("2025-07-01", "0.50"),
+                    ],
+                },
+                DividendData {
+                    symbol: "PAYEE".to_string(),
+                    data: vec![record("2025-02-14", "0.06"), record("2025-01-02", "0.05")],
+                },
+            ] {
+                std::fs::write(
+                    dir.join(format!("{}.json", dividends.symbol)),
+                    serde_json::to_string(&dividends).unwrap(),
+                )
+                .unwrap();
+            }
+
+            let data = DataOptions {
+                dividend_data_path: Some(archive.path().to_path_buf()),
+                ..DataOptions::default()
+            };
+            (archive, data)
+        })
+        .1
+}
+
+/// Performance options reading the shared archive.
+fn dividend_options() -> PerformanceOptions {
+    PerformanceOptions {
+        data: dividend_archive().clone(),
+        ..PerformanceOptions::default()
+    }
+}
+
+#[test]
+fn test_sums_only_ex_dates_inside_the_range() {
+    let options = dividend_options();
+
+    // Both boundaries are inclusive; 2024-12-31 and 2025-07-01 fall outside.
+    let total =
+        calculate_dividends_for_period("NYSE:PAYER", "2025-01-02", "2025-04-02", &options).unwrap();
+    assert!((total - 0.90).abs() < 1e-9, "{total}");
+
+    let total =
+        calculate_dividends_for_period("NYSE:PAYER", "2025-01-03", "2025-04-01", &options).unwrap();
+    assert!((total - 0.30).abs() < 1e-9, "{total}");
+}
+
+#[test]
+fn test_ticker_without_dividend_file_returns_zero() {
+    let options = dividend_options();
+
+    let total =
+        calculate_dividends_for_period("NYSE:NOPAY", "2025-01-01", "2025-12-31", &options).unwrap();
+    assert_eq!(total, 0.0);
+    assert!(total.is_sign_positive());
+}
+
+#[test]
+fn test_dividend_csv_is_sorted_by_date_then_ticker() {
+    let data = dividend_archive();
+    let out = tempfile::tempdir().unwrap();
+    let output = out.path().join("dividends.csv");
+
+    create_dividend_csv_with_options(
+        &["NYSE:PAYER".to_string(), "NYSE:PAYEE".to_string()],
+        "2025-01-01",
+        &output.to_string_lossy(),
+        data,
+    )
+    .unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "date,symbol,amount\n\
+         2025-01-02,NYSE:PAYEE,0.05\n\
+         2025-01-02,NYSE:PAYER,0.2\n\
+         2025-02-14,NYSE:PAYEE,0.06\n\
+         2025-02-14,NYSE:PAYER,0.3\n\
+         2025-04-02,NYSE:PAYER,0.4\n"
+    );
+}
@@ -0,0 +1,67 @@
+//! Reading `index.json` over HTTP, and refusing to write one back to a URL.
+
+use grq_validation::error::GrqError;
+use grq_validation::models::IndexData;
+use grq_validation::utils::{diff_index, read_index_json_from_url, write_index_atomic};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+/// Serves one HTTP request on a local port with `status` and `body`, returning
+/// the URL of `/scores/index.json` on it.
+fn serve_once(status: &'static str, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 4096];
+        let _ = stream.read(&mut request).unwrap();
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+    });
+    format!("http://127.0.0.1:{port}/scores/index.json")
+}
+
+const INDEX: &str = r#"{"scores": [
+    {"year": "2025", "month": "February", "day": "01", "date": "2025-02-01", "file": "2025/February/01.tsv", "performance_90_day": 4.0},
+    {"year": "2025", "month": "January", "day": "01", "date": "2025-01-01", "file": "2025/January/01.tsv", "performance_90_day": 2.0}
+]}"#;
+
+#[test]
+fn reads_a_published_index_sorted_by_date() {
+    let index = read_index_json_from_url(&serve_once("200 OK", INDEX)).unwrap();
+    let dates: Vec<&str> = index.scores.iter().map(|e| e.date.as_str()).collect();
+    assert_eq!(dates, ["2025-01-01", "2025-02-01"]);
+    assert_eq!(index.scores[1].performance_90_day, Some(4.0));
+}
+
+#[test]
+fn an_error_status_is_an_http_error() {
+    let error = read_index_json_from_url(&serve_once("404 Not Found", "missing")).unwrap_err();
+    assert!(matches!(error, GrqError::Http(_)), "{error:?}");
+}
+
+#[test]
+fn diff_compares_a_local_index_with_a_published_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let local = dir.path().join("index.json");
+    std::fs::write(
+        &local,
+        r#"{"scores": [{"year": "2025", "month": "January", "day": "01", "date": "2025-01-01", "file": "2025/January/01.tsv", "performance_90_day": 2.0}]}"#,
+    )
+    .unwrap();
+
+    let changes = diff_index(&local, serve_once("200 OK", INDEX), 0.0).unwrap();
+    let dates: Vec<&str> = changes.iter().map(|c| c.date.as_str()).collect();
+    assert_eq!(dates, ["2025-02-01"]);
+}
+
+#[test]
+fn writing_the_index_to_a_url_is_refused() {
+    let index: IndexData = serde_json::from_str(INDEX).unwrap();
+    let error = write_index_atomic("https://example.com/scores/index.json", &index).unwrap_err();
+    assert!(matches!(error, GrqError::ReadOnly(_)), "{error:?}");
+}
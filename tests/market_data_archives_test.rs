@@ -0,0 +1,61 @@
+//! Market data split across quarterly archives.
+//!
+//! Each share-price archive (`../GRQ-shareprices2025Q1`, `…Q2`, …) holds one
+//! quarter, so a 90-day window from a March score date needs June prices from
+//! the next archive, so this test reads from two archives.
+
+mod common;
+
+use chrono::NaiveDate;
+use common::write_prices;
+use grq_validation::utils::{read_market_data_window, read_market_data_with_options, DataOptions};
+
+const SYMBOL: &str = "GRQVARCHIVE";
+
+#[test]
+fn test_window_spanning_a_quarter_merges_archives() {
+    let q1 = tempfile::tempdir().unwrap();
+    let q2 = tempfile::tempdir().unwrap();
+    // Q1 was last refreshed when March 31 was provisional; Q2 restates it.
+    write_prices(
+        q1.path(),
+        SYMBOL,
+        "2025-03-31",
+        &[("2025-03-14", "100.0"), ("2025-03-31", "101.0")],
+    );
+    write_prices(
+        q2.path(),
+        SYMBOL,
+        "2025-06-30",
+        &[("2025-03-31", "999.0"), ("2025-06-10", "110.0")],
+    );
+    let data = DataOptions {
+        market_data_paths: vec![q1.path().to_path_buf(), q2.path().to_path_buf()],
+        ..DataOptions::default()
+    };
+
+    let score_date = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+    let window = read_market_data_window(
+        SYMBOL,
+        score_date,
+        score_date + chrono::Duration::days(90),
+        &data,
+    )
+    .unwrap();
+    let mut dates: Vec<&str> = window
+        .time_series_daily
+        .keys()
+        .map(String::as_str)
+        .collect();
+    dates.sort_unstable();
+    assert_eq!(dates, ["2025-03-14", "2025-03-31", "2025-06-10"]);
+    // The earlier archive wins an overlapping date; the newest refresh is kept.
+    assert_eq!(window.time_series_daily["2025-03-31"].close, "101.0");
+    assert_eq!(window.meta_data.last_refreshed, "2025-06-30");
+
+    let full = read_market_data_with_options(SYMBOL, &data).unwrap();
+    assert_eq!(full.time_series_daily.len(), 3);
+
+    // A ticker missing from every archive is still an error.
+    assert!(read_market_data_with_options("GRQVMISSING", &data).is_err());
+}
@@ -0,0 +1,72 @@
+//! A cache directory keeps each parsed market-data window in binary form and
+//! serves it until the source file's modification time changes.
+
+mod common;
+
+use chrono::NaiveDate;
+use grq_validation::utils::{read_market_data_window, DataOptions};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Writes `SLOW`'s market-data file into `archive` with a single close on
+/// 2025-06-02 and stamps it with `modified`.
+fn write_prices(archive: &Path, close: &str, modified: SystemTime) {
+    let path = common::write_prices(archive, "SLOW", "2025-06-30", &[("2025-06-02", close)]);
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+fn close(data: &DataOptions) -> String {
+    let date = |day| NaiveDate::from_ymd_opt(2025, 6, day).unwrap();
+    read_market_data_window("NYSE:SLOW", date(1), date(30), data)
+        .unwrap()
+        .time_series_daily["2025-06-02"]
+        .close
+        .clone()
+}
+
+#[test]
+fn windows_are_served_from_the_cache_until_the_source_changes() {
+    let archive = tempfile::tempdir().unwrap();
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000);
+    write_prices(archive.path(), "10.00", modified);
+    let cache = tempfile::tempdir().unwrap();
+    let cached = DataOptions {
+        market_data_paths: vec![archive.path().to_path_buf()],
+        cache_dir: Some(cache.path().to_path_buf()),
+        ..DataOptions::default()
+    };
+    let uncached = DataOptions {
+        cache_dir: None,
+        ..cached.clone()
+    };
+
+    assert_eq!(close(&cached), "10.00");
+    assert!(cache
+        .path()
+        .join("data/S/SLOW-2025-06-01-2025-06-30.bin")
+        .is_file());
+
+    // New contents behind an unchanged modification time: the cached window
+    // is still served, showing the JSON was not parsed again.
+    write_prices(archive.path(), "11.00", modified);
+    assert_eq!(close(&cached), "10.00");
+
+    // With the cache off, the JSON is read.
+    assert_eq!(close(&uncached), "11.00");
+
+    // A newer source invalidates the cached window.
+    write_prices(archive.path(), "12.00", modified + Duration::from_secs(60));
+    assert_eq!(close(&cached), "12.00");
+    write_prices(archive.path(), "13.00", modified + Duration::from_secs(60));
+    assert_eq!(
+        close(&cached),
+        "12.00",
+        "the refreshed window is cached again"
+    );
+}
@@ -0,0 +1,96 @@
+//! Share prices read from a flat `ticker,date,close` CSV instead of the JSON
+//! archive.
+
+use grq_validation::utils::{
+    calculate_performance_for_tickers, create_market_data_long_csv_with_options,
+    ensure_market_data_repository_with_options, read_market_data_window,
+    read_market_data_with_options, verify_generated_csv, CsvKind, DataOptions, PerformanceOptions,
+};
+use std::sync::OnceLock;
+
+/// Loads the shared price CSV once, pointing the JSON archive at an empty
+/// directory so any read that misses the CSV fails. Returns data options
+/// reading it.
+fn price_csv() -> &'static DataOptions {
+    static DIRS: OnceLock<(tempfile::TempDir, DataOptions)> = OnceLock::new();
+    &DIRS
+        .get_or_init(|| {
+            let dir = tempfile::tempdir().unwrap();
+            let csv = dir.path().join("prices.csv");
+            std::fs::write(
+                &csv,
+                "ticker,date,close\n\
+             NYSE:FLATA,2025-06-20,100.0\n\
+             NYSE:FLATA,2025-09-18,112.0\n\
+             FLATB,2025-06-20,30.0\n\
+             FLATB,2025-09-18,27.0\n",
+            )
+            .unwrap();
+            let mut data = DataOptions {
+                market_data_paths: vec![dir.path().join("no-archive")],
+                dividend_data_path: Some(dir.path().join("no-dividends")),
+                ..DataOptions::default()
+            };
+            data.load_market_data_csv(&csv).unwrap();
+            (dir, data)
+        })
+        .1
+}
+
+#[test]
+fn test_csv_prices_replace_the_json_archive() {
+    let data = price_csv();
+    ensure_market_data_repository_with_options(data).expect("a CSV source needs no archive");
+
+    let market_data = read_market_data_with_options("FLATA", data).unwrap();
+    assert_eq!(market_data.time_series_daily.len(), 2);
+    assert_eq!(market_data.time_series_daily["2025-09-18"].close, "112");
+    assert_eq!(market_data.meta_data.last_refreshed, "2025-09-18");
+
+    // A window keeps only its own days, and a full ticker finds its symbol.
+    let window = read_market_data_window(
+        "NYSE:FLATB",
+        "2025-06-01".parse().unwrap(),
+        "2025-06-30".parse().unwrap(),
+        data,
+    )
+    .unwrap();
+    assert_eq!(
+        window.time_series_daily.keys().collect::<Vec<_>>(),
+        ["2025-06-20"]
+    );
+
+    assert!(read_market_data_with_options("MISSING", data).is_err());
+}
+
+#[test]
+fn test_csv_prices_feed_performance() {
+    let options = PerformanceOptions {
+        data: price_csv().clone(),
+        ..PerformanceOptions::default()
+    };
+    let tickers = vec!["NYSE:FLATA".to_string(), "NYSE:FLATB".to_string()];
+
+    let performance = calculate_performance_for_tickers(&tickers, "2025-06-20", &options).unwrap();
+    assert_eq!(performance.total_stocks, 2);
+    // (+12% + -10%) / 2
+    assert!((performance.performance_90_day - 1.0).abs() < 1e-9);
+
+    let out = tempfile::tempdir().unwrap();
+    let output = out.path().join("20.csv");
+    create_market_data_long_csv_with_options(
+        &tickers,
+        "2025-06-20",
+        &output.to_string_lossy(),
+        &options.data,
+    )
+    .unwrap();
+    let csv = std::fs::read_to_string(&output).unwrap();
+    assert!(csv.contains("2025-09-18,NYSE:FLATB,"), "{csv}");
+    // CSV prices carry no volume, which the CSV records as 0 so that
+    // --verify accepts it.
+    assert_eq!(
+        verify_generated_csv(&output.to_string_lossy(), CsvKind::Market),
+        Vec::new()
+    );
+}
@@ -0,0 +1,101 @@
+//! Scoring records that are not in the docs tree (e.g. piped in on stdin).
+//!
+//! `market_data_for_tickers` reads straight from the share-price archive, so
+//! the test points it at a temporary one.
+
+mod common;
+
+use grq_validation::error::GrqError;
+use grq_validation::utils::{
+    calculate_performance_for_tickers, calculate_portfolio_performance_for_records,
+    calculate_portfolio_performance_with_options, create_market_data_long_csv_with_options,
+    market_data_for_tickers, read_tsv_score_reader, DataOptions, PerformanceOptions,
+};
+
+const SCORE_TSV: &str = "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+                         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+                         NYSE:PIPEA\t0.9\t$120.00\t\t\t\t\t\n\
+                         NYSE:PIPEB\t0.5\t$40.00\t\t\t\t\t\n";
+
+/// Writes `symbol`'s market-data file with one close per `(date, close)`.
+fn write_prices(base: &std::path::Path, symbol: &str, closes: &[(&str, &str)]) {
+    common::write_prices(base, symbol, "2025-10-01", closes);
+}
+
+#[test]
+fn test_piped_records_score_like_a_score_file() {
+    let archive = tempfile::tempdir().unwrap();
+    write_prices(
+        archive.path(),
+        "PIPEA",
+        &[("2025-06-20", "100.0"), ("2025-09-18", "112.0")],
+    );
+    write_prices(
+        archive.path(),
+        "PIPEB",
+        &[("2025-06-20", "30.0"), ("2025-09-18", "27.0")],
+    );
+    let options = PerformanceOptions {
+        data: DataOptions {
+            market_data_paths: vec![archive.path().to_path_buf()],
+            dividend_data_path: Some(archive.path().to_path_buf()),
+            ..DataOptions::default()
+        },
+        ..PerformanceOptions::default()
+    };
+
+    let records = read_tsv_score_reader(SCORE_TSV.as_bytes(), "<stdin>").unwrap();
+    let tickers: Vec<String> = records.iter().map(|r| r.stock.clone()).collect();
+    let market = market_data_for_tickers(&tickers, "2025-06-20", &options.data).unwrap();
+    let piped =
+        calculate_portfolio_performance_for_records(&records, &market, "2025-06-20", &options)
+            .unwrap();
+    assert_eq!(piped.total_stocks, 2);
+    assert!((piped.performance_90_day - 1.0).abs() < 1e-9);
+
+    // The same picks written to disk give the same figures.
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("20.tsv");
+    std::fs::write(&score_path, SCORE_TSV).unwrap();
+    create_market_data_long_csv_with_options(
+        &tickers,
+        "2025-06-20",
+        &docs.path().join("20.csv").to_string_lossy(),
+        &options.data,
+    )
+    .unwrap();
+    let on_disk = calculate_portfolio_performance_with_options(
+        &score_path.to_string_lossy(),
+        "2025-06-20",
+        &options,
+    )
+    .unwrap();
+    assert_eq!(
+        on_disk.performance_90_day.to_bits(),
+        piped.performance_90_day.to_bits()
+    );
+
+    let missing = market_data_for_tickers(
+        &["NYSE:PIPEMISSING".to_string()],
+        "2025-06-20",
+        &options.data,
+    );
+    assert!(missing.is_err());
+
+    // A bare ticker list, with a repeat, scores the same basket.
+    let basket = [tickers.clone(), vec![tickers[0].clone()]].concat();
+    let ad_hoc = calculate_performance_for_tickers(&basket, "2025-06-20", &options).unwrap();
+    assert_eq!(ad_hoc.total_stocks, 2);
+    assert_eq!(
+        ad_hoc.performance_90_day.to_bits(),
+        piped.performance_90_day.to_bits()
+    );
+    assert!(ad_hoc
+        .individual_performances
+        .iter()
+        .all(|stock| stock.target_hit_date.is_none()));
+    assert!(matches!(
+        calculate_performance_for_tickers(&[], "2025-06-20", &options),
+        Err(GrqError::NoStockRecords(_))
+    ));
+}
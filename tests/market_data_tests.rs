@@ -1,15 +1,15 @@
 use anyhow::Result;
 use grq_validation::utils::{
     create_market_data_long_csv_for_score_file, extract_symbol_from_ticker,
-    extract_ticker_codes_from_score_file, get_market_data_path, MARKET_DATA_BASE_PATH,
+    extract_ticker_codes_from_score_file, get_market_data_path, DataOptions,
 };
 
 /// Best-effort smoke test against the external share-price repository. It only
 /// asserts when real market data for this score file's date is genuinely
 /// present; otherwise it skips.
 ///
-/// The guard must NOT be a bare `MARKET_DATA_BASE_PATH` existence check.
-/// Sibling tests (`create_market_data_csv_test.rs`,
+/// The guard must NOT be a bare `DataOptions::market_data_base_path` existence
+/// check. Sibling tests (`create_market_data_csv_test.rs`,
 /// `create_market_data_long_csv_test.rs`) drop synthetic fixtures under that
 /// same base directory, so a bare existence check is a shared, mutable sentinel
 /// that another test can transiently satisfy — making this test run against a
@@ -41,7 +41,8 @@ fn test_create_market_data_long_csv_for_first_score_file() -> Result<()> {
     if !has_real_data {
         println!(
             "Skipping test_create_market_data_long_csv_for_first_score_file: \
-             no market data for this score file's tickers under {MARKET_DATA_BASE_PATH}"
+             no market data for this score file's tickers under {}",
+            DataOptions::default().market_data_base_path().display()
         );
         return Ok(());
     }
@@ -0,0 +1,117 @@
+//! Memory behaviour of `read_market_data_window`.
+//!
+//! Reading a whole multi-year market-data JSON file just to keep a 180-day
+//! window meant holding every day in memory at once. This test installs a
+//! counting global allocator (which is why it lives in its own test binary) and
+//! compares the peak heap use of the full read with the windowed one over the
+//! same synthetic 20-year series.
+
+use chrono::{Duration, NaiveDate};
+use grq_validation::utils::{read_market_data_window, read_market_data_with_options, DataOptions};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// Runs `read` and returns how far the heap grew above its starting size.
+fn peak_growth<T>(read: impl FnOnce() -> T) -> (T, usize) {
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let value = read();
+    (value, PEAK.load(Ordering::Relaxed) - baseline)
+}
+
+/// A market-data document with one entry per day for 20 years from 2006.
+fn twenty_year_series(symbol: &str) -> String {
+    let first = NaiveDate::from_ymd_opt(2006, 1, 1).unwrap();
+    let series: serde_json::Map<String, serde_json::Value> = (0..365 * 20)
+        .map(|offset| {
+            let date = first + Duration::days(offset);
+            let price = format!("{:.2}", 100.0 + (offset % 50) as f64);
+            (
+                date.format("%Y-%m-%d").to_string(),
+                serde_json::json!({
+                    "1. open": price,
+                    "2. high": price,
+                    "3. low": price,
+                    "4. close": price,
+                    "5. adjusted close": price,
+                    "6. volume": "1000",
+                    "7. dividend amount": "0.0000",
+                    "8. split coefficient": "1.0",
+                }),
+            )
+        })
+        .collect();
+
+    serde_json::json!({
+        "Meta Data": {
+            "1. Information": "Daily Prices (fixture)",
+            "2. Symbol": symbol,
+            "3. Last Refreshed": "2025-12-31",
+            "4. Output Size": "Full size",
+            "5. Time Zone": "US/Eastern",
+        },
+        "Time Series (Daily)": series,
+    })
+    .to_string()
+}
+
+#[test]
+fn test_windowed_read_bounds_peak_memory() {
+    let symbol = "GRQVWINDOW";
+    let dir = tempfile::tempdir().unwrap();
+    let symbol_dir = dir.path().join("data").join("G");
+    std::fs::create_dir_all(&symbol_dir).unwrap();
+    std::fs::write(
+        symbol_dir.join(format!("{symbol}.json")),
+        twenty_year_series(symbol),
+    )
+    .unwrap();
+    let data = DataOptions {
+        market_data_paths: vec![dir.path().to_path_buf()],
+        ..DataOptions::default()
+    };
+
+    let start = NaiveDate::from_ymd_opt(2025, 4, 15).unwrap();
+    let end = start + Duration::days(180);
+
+    let (full, full_peak) = peak_growth(|| read_market_data_with_options(symbol, &data).unwrap());
+    let full_len = full.time_series_daily.len();
+    drop(full);
+    let (window, window_peak) =
+        peak_growth(|| read_market_data_window(symbol, start, end, &data).unwrap());
+
+    assert_eq!(full_len, 365 * 20);
+    assert_eq!(window.time_series_daily.len(), 181);
+    assert!(window.time_series_daily.contains_key("2025-04-15"));
+    assert!(window.time_series_daily.contains_key("2025-10-12"));
+    assert!(!window.time_series_daily.contains_key("2025-10-13"));
+    assert_eq!(window.meta_data.symbol, symbol);
+    assert!(
+        window_peak * 10 < full_peak,
+        "windowed read peaked at {window_peak} bytes, full read at {full_peak}"
+    );
+}
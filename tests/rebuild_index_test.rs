@@ -0,0 +1,108 @@
+//! `rebuild_index` recreates `docs/scores/index.json` from the score files on
+//! disk, keeping the performance figures already recorded for their dates.
+
+use grq_validation::utils::rebuild_index;
+use std::fs;
+use std::path::Path;
+
+/// Writes `contents` to `path`, creating parent directories as needed.
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("create parent directories");
+    }
+    fs::write(path, contents).expect("write fixture file");
+}
+
+const TSV: &str = "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+                   intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+                   NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n";
+
+#[test]
+fn rebuilds_entries_from_the_tree_and_keeps_known_figures() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    for file in [
+        "2025/January/15.tsv",
+        "2025/February/03.tsv",
+        "2025/2025-03-20.tsv",
+        // Same date as the ISO-named file above, so it is skipped.
+        "2025/March/20.tsv",
+        // Names no date.
+        "2025/notes.tsv",
+    ] {
+        write_file(&scores.join(file), TSV);
+    }
+    write_file(&scores.join("2025/January/15.csv"), "not a score file");
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15",
+      "performance_90_day": 10.0, "performance_annualized": 46.0, "total_stocks": 1
+    },
+    {
+      "year": "2025", "month": "March", "day": "20",
+      "file": "2025/March/20.tsv", "date": "2025-03-20",
+      "performance_90_day": 4.0
+    },
+    {
+      "year": "2024", "month": "December", "day": "1",
+      "file": "2024/December/1.tsv", "date": "2024-12-01",
+      "performance_90_day": 1.0
+    }
+  ]
+}"#,
+    );
+
+    let index = rebuild_index(docs.to_str().unwrap()).expect("rebuild should succeed");
+
+    let rows: Vec<(&str, &str, &str, &str, &str)> = index
+        .scores
+        .iter()
+        .map(|entry| {
+            (
+                entry.date.as_str(),
+                entry.year.as_str(),
+                entry.month.as_str(),
+                entry.day.as_str(),
+                entry.file.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            ("2025-01-15", "2025", "January", "15", "2025/January/15.tsv"),
+            (
+                "2025-02-03",
+                "2025",
+                "February",
+                "03",
+                "2025/February/03.tsv"
+            ),
+            ("2025-03-20", "2025", "March", "20", "2025/2025-03-20.tsv"),
+        ]
+    );
+    assert_eq!(index.scores[0].performance_90_day, Some(10.0));
+    assert_eq!(index.scores[0].total_stocks, Some(1));
+    assert_eq!(index.scores[1].performance_90_day, None);
+    // The date moved to another file but keeps its figures.
+    assert_eq!(index.scores[2].performance_90_day, Some(4.0));
+}
+
+#[test]
+fn rebuilds_without_an_existing_index() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    write_file(&docs.join("scores/2025/June/5.tsv"), TSV);
+
+    let index = rebuild_index(docs.to_str().unwrap()).expect("rebuild should succeed");
+
+    assert_eq!(index.scores.len(), 1);
+    assert_eq!(index.scores[0].date, "2025-06-05");
+    assert_eq!(index.scores[0].day, "5");
+    assert_eq!(index.scores[0].performance_90_day, None);
+}
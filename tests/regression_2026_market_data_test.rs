@@ -49,7 +49,7 @@ fn restored_2026_csvs_have_real_ohlc_rows() {
         let row_count: usize = market
             .closes
             .values()
-            .map(std::collections::HashMap::len)
+            .map(std::collections::BTreeMap::len)
             .sum();
         assert!(
             row_count > 0,
@@ -0,0 +1,91 @@
+//! The dividend data read while writing a score file's dividend CSV is reused
+//! by its performance calculation instead of being read a second time.
+
+use grq_validation::utils::{
+    calculate_portfolio_performance_with_options, create_dividend_csv_for_score_file_with_options,
+    DataOptions, PerformanceOptions,
+};
+use std::fs;
+
+#[test]
+fn performance_reuses_the_dividends_read_for_the_csv() {
+    let archive = tempfile::tempdir().unwrap();
+    let symbol_dir = archive.path().join("data").join("D");
+    fs::create_dir_all(&symbol_dir).unwrap();
+    let dividend_file = symbol_dir.join("DIVI.json");
+    fs::write(
+        &dividend_file,
+        r#"{"symbol": "DIVI", "data": [
+            {"ex_dividend_date": "2025-02-15", "declaration_date": null,
+             "record_date": null, "payment_date": null, "amount": "0.50"}
+        ]}"#,
+    )
+    .unwrap();
+    let data = DataOptions {
+        dividend_data_path: Some(archive.path().to_path_buf()),
+        ..DataOptions::default()
+    };
+
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("15.tsv");
+    fs::write(
+        &score_path,
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:DIVI\t1.0\t$120.00\t\t\t\t\t\n\
+         NYSE:NONE\t1.0\t$120.00\t\t\t\t\t\n",
+    )
+    .unwrap();
+    fs::write(
+        docs.path().join("15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:DIVI,0,0,0,100.0\n\
+         2025-04-15,NYSE:DIVI,0,0,0,110.0\n\
+         2025-01-15,NYSE:NONE,0,0,0,100.0\n\
+         2025-04-15,NYSE:NONE,0,0,0,110.0\n",
+    )
+    .unwrap();
+    let score_path = score_path.to_string_lossy();
+    let tickers = ["NYSE:DIVI".to_string(), "NYSE:NONE".to_string()];
+
+    let shared =
+        create_dividend_csv_for_score_file_with_options(&score_path, &tickers, "2025-01-15", &data)
+            .unwrap();
+    assert_eq!(shared.len(), 2, "every ticker is recorded, payer or not");
+    assert_eq!(shared["NYSE:DIVI"].data.len(), 1);
+    assert!(shared["NYSE:NONE"].data.is_empty());
+
+    // With the file gone, a second open could only find nothing: any
+    // dividend the performance pass still sees came from the shared data.
+    fs::remove_file(&dividend_file).unwrap();
+
+    let options = PerformanceOptions {
+        shared_dividends: Some(shared),
+        data: data.clone(),
+        ..PerformanceOptions::default()
+    };
+    let performance =
+        calculate_portfolio_performance_with_options(&score_path, "2025-01-15", &options).unwrap();
+    let dividends_total = |performance: &grq_validation::models::PortfolioPerformance, ticker| {
+        performance
+            .individual_performances
+            .iter()
+            .find(|stock| stock.ticker == ticker)
+            .unwrap()
+            .dividends_total
+    };
+    assert!((dividends_total(&performance, "NYSE:DIVI") - 0.50).abs() < 1e-12);
+    assert_eq!(dividends_total(&performance, "NYSE:NONE"), 0.0);
+
+    // Without it the same calculation reads the (now missing) file itself.
+    let performance = calculate_portfolio_performance_with_options(
+        &score_path,
+        "2025-01-15",
+        &PerformanceOptions {
+            data,
+            ..PerformanceOptions::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(dividends_total(&performance, "NYSE:DIVI"), 0.0);
+}
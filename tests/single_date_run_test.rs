@@ -0,0 +1,84 @@
+//! End-to-end tests of a `--date` run against a temporary docs tree.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Writes a settled 2024-12-03 score file holding `NYSE:TEST` under the
+/// archive's month-name layout, with a market CSV gaining 10% over the 90
+/// days, and an `index.json` whose entry records the date as `index_date`.
+fn write_docs(docs: &Path, index_date: &str) {
+    let december = docs.join("scores").join("2024").join("December");
+    std::fs::create_dir_all(&december).unwrap();
+    std::fs::write(
+        december.join("3.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t12.00\t\t\t\t\t\n",
+    )
+    .unwrap();
+    std::fs::write(
+        december.join("3.csv"),
+        "date,ticker,high,low,open,close\n\
+         2024-12-03,NYSE:TEST,0,0,0,10.0\n\
+         2025-03-03,NYSE:TEST,0,0,0,11.0\n",
+    )
+    .unwrap();
+    std::fs::write(
+        docs.join("scores").join("index.json"),
+        format!(
+            r#"{{"scores": [{{"year": "2024", "month": "December", "day": "3",
+                "file": "2024/December/3.tsv", "date": "{index_date}"}}]}}"#
+        ),
+    )
+    .unwrap();
+}
+
+/// Runs the binary for `date` over `docs` with no dividend data.
+fn run_for_date(docs: &Path, date: &str) -> std::process::Output {
+    let no_dividends = tempfile::tempdir().unwrap();
+    Command::new(env!("CARGO_BIN_EXE_grq-validation"))
+        .args([
+            "--date",
+            date,
+            "--docs-path",
+            docs.to_str().unwrap(),
+            "--dividend-data-path",
+            no_dividends.path().to_str().unwrap(),
+            "--no-cache",
+        ])
+        .output()
+        .expect("run grq-validation binary")
+}
+
+#[test]
+fn a_padded_date_updates_an_unpadded_index_entry() {
+    let docs = tempfile::tempdir().unwrap();
+    write_docs(docs.path(), "2024-12-3");
+
+    let output = run_for_date(docs.path(), "2024-12-03");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+
+    let index = grq_validation::utils::read_index_json(docs.path().to_str().unwrap()).unwrap();
+    let entry = &index.scores[0];
+    assert_eq!(entry.date, "2024-12-3");
+    let performance = entry.performance_90_day.expect("performance recorded");
+    assert!((performance - 10.0).abs() < 1e-6, "got {performance}");
+}
+
+#[test]
+fn a_date_missing_from_the_index_is_reported_and_leaves_it_unchanged() {
+    let docs = tempfile::tempdir().unwrap();
+    write_docs(docs.path(), "2024-12-04");
+    let index_path = docs.path().join("scores").join("index.json");
+    let before = std::fs::read_to_string(&index_path).unwrap();
+
+    let output = run_for_date(docs.path(), "2024-12-03");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(
+        stderr.contains("index.json has no entry for 2024-12-03"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(std::fs::read_to_string(&index_path).unwrap(), before);
+}
@@ -0,0 +1,351 @@
+//! `--strict` turns skipped-data warnings into errors.
+//!
+mod common;
+
+use grq_validation::error::GrqError;
+use grq_validation::models::StockRecord;
+use grq_validation::utils::{
+    calculate_hybrid_projection_with_options, calculate_portfolio_performance_with_options,
+    create_dividend_csv_with_options, create_market_data_long_csv_with_options,
+    update_index_with_performance_with_options, DataOptions, FixedClock, IndexUpdateOptions,
+    PerformanceOptions, ProjectionOptions,
+};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// Writes `symbol`'s market-data file with a single close on 2025-06-02.
+fn write_prices(base: &Path, symbol: &str, close: &str) {
+    common::write_prices(base, symbol, "2025-06-30", &[("2025-06-02", close)]);
+}
+
+/// Sets up the shared archives once and returns strict data options reading
+/// them:
+/// - `GOOD` has valid prices and no dividend file;
+/// - `BADCLOSE` has a non-numeric close;
+/// - `CORRUPT` has a price file that is not JSON;
+/// - `DIVBAD` has valid prices and a non-numeric dividend amount;
+/// - `PAYER` has valid prices and a dividend long after June 2025.
+fn strict_data() -> &'static DataOptions {
+    static ARCHIVES: OnceLock<(tempfile::TempDir, tempfile::TempDir, DataOptions)> =
+        OnceLock::new();
+    &ARCHIVES
+        .get_or_init(|| {
+            let prices = tempfile::tempdir().unwrap();
+            write_prices(prices.path(), "GOOD", "10.00");
+            write_prices(prices.path(), "BADCLOSE", "N/A");
+            write_prices(prices.path(), "DIVBAD", "20.00");
+            write_prices(prices.path(), "PAYER", "30.00");
+            std::fs::create_dir_all(prices.path().join("data").join("C")).unwrap();
+            std::fs::write(
+                prices.path().join("data").join("C").join("CORRUPT.json"),
+                "{ not json",
+            )
+            .unwrap();
+
+            let dividends = tempfile::tempdir().unwrap();
+            std::fs::create_dir_all(dividends.path().join("data").join("D")).unwrap();
+            std::fs::write(
+                dividends.path().join("data").join("D").join("DIVBAD.json"),
+                r#"{"symbol": "DIVBAD", "data": [
+                {"ex_dividend_date": "2025-06-10", "declaration_date": null,
+                 "record_date": null, "payment_date": null, "amount": "abc"}
+            ]}"#,
+            )
+            .unwrap();
+            std::fs::create_dir_all(dividends.path().join("data").join("P")).unwrap();
+            std::fs::write(
+                dividends.path().join("data").join("P").join("PAYER.json"),
+                r#"{"symbol": "PAYER", "data": [
+                {"ex_dividend_date": "2026-03-10", "declaration_date": null,
+                 "record_date": null, "payment_date": null, "amount": "0.50"}
+            ]}"#,
+            )
+            .unwrap();
+
+            let data = DataOptions {
+                market_data_paths: vec![prices.path().to_path_buf()],
+                dividend_data_path: Some(dividends.path().to_path_buf()),
+                strict: true,
+                ..DataOptions::default()
+            };
+            (prices, dividends, data)
+        })
+        .2
+}
+
+/// Performance options reading the strict archives.
+fn strict_performance() -> PerformanceOptions {
+    PerformanceOptions {
+        data: strict_data().clone(),
+        ..PerformanceOptions::default()
+    }
+}
+
+fn tickers(symbols: &[&str]) -> Vec<String> {
+    symbols
+        .iter()
+        .map(|symbol| format!("NYSE:{symbol}"))
+        .collect()
+}
+
+/// The date `year-month-day`.
+fn date(year: i32, month: u32, day: u32) -> chrono::NaiveDate {
+    chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+#[test]
+fn test_strict_market_data_csv_fails_on_unreadable_or_malformed_prices() {
+    let data = strict_data();
+    let out = tempfile::tempdir().unwrap();
+
+    for bad in ["CORRUPT", "BADCLOSE"] {
+        let output = out.path().join(format!("{bad}.csv"));
+        let error = create_market_data_long_csv_with_options(
+            &tickers(&["GOOD", bad]),
+            "2025-06-01",
+            &output.to_string_lossy(),
+            data,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(error, GrqError::StrictModeViolation(_)),
+            "{bad}: {error}"
+        );
+        assert!(!output.exists(), "{bad}: no partial CSV may be written");
+    }
+
+    // A ticker with no price file at all is still skipped.
+    let output = out.path().join("missing.csv");
+    create_market_data_long_csv_with_options(
+        &tickers(&["GOOD", "NOFILE"]),
+        "2025-06-01",
+        &output.to_string_lossy(),
+        data,
+    )
+    .unwrap();
+    let csv = std::fs::read_to_string(&output).unwrap();
+    assert!(csv.contains("2025-06-02,NYSE:GOOD,"));
+}
+
+#[test]
+fn test_strict_dividend_csv_fails_on_malformed_amount() {
+    let data = strict_data();
+    let out = tempfile::tempdir().unwrap();
+
+    let output = out.path().join("dividends.csv");
+    let error = create_dividend_csv_with_options(
+        &tickers(&["GOOD", "DIVBAD"]),
+        "2025-06-01",
+        &output.to_string_lossy(),
+        data,
+    )
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("dividend amount 'abc'"),
+        "{error}"
+    );
+    assert!(!output.exists(), "no partial CSV may be written");
+
+    // GOOD has no dividend file, which is normal and not an error.
+    create_dividend_csv_with_options(
+        &tickers(&["GOOD"]),
+        "2025-06-01",
+        &output.to_string_lossy(),
+        data,
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        "date,symbol,amount\n"
+    );
+}
+
+#[test]
+fn test_strict_performance_fails_when_a_declared_dividend_has_no_data() {
+    let options = strict_performance();
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("01.tsv");
+    std::fs::write(
+        docs.path().join("01.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-06-02,NYSE:GOOD,0,0,0,10.0\n\
+         2025-08-29,NYSE:GOOD,0,0,0,11.0\n",
+    )
+    .unwrap();
+    let score_path = score_path.to_string_lossy();
+    let write_score_file = |dividend_per_share: &str| {
+        std::fs::write(
+            &*score_path,
+            format!(
+                "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+                 intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+                 NYSE:GOOD\t1.0\t$12.00\t\t{dividend_per_share}\t\t\t\n"
+            ),
+        )
+        .unwrap();
+    };
+
+    // GOOD has no dividend file, so a declared dividend cannot be credited.
+    write_score_file("0.25");
+    let error = calculate_portfolio_performance_with_options(&score_path, "2025-06-01", &options)
+        .unwrap_err();
+    assert!(matches!(error, GrqError::StrictModeViolation(_)), "{error}");
+    assert!(error.to_string().contains("NYSE:GOOD declares a dividend"));
+
+    // Without a declared dividend, no dividends is the expected outcome.
+    write_score_file("");
+    let performance =
+        calculate_portfolio_performance_with_options(&score_path, "2025-06-01", &options).unwrap();
+    assert!((performance.performance_90_day - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_strict_performance_fails_on_a_malformed_dividend_amount() {
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("01.tsv");
+    std::fs::write(
+        &score_path,
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:DIVBAD\t1.0\t$24.00\t\t\t\t\t\n",
+    )
+    .unwrap();
+    std::fs::write(
+        docs.path().join("01.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-06-02,NYSE:DIVBAD,0,0,0,20.0\n\
+         2025-08-29,NYSE:DIVBAD,0,0,0,22.0\n",
+    )
+    .unwrap();
+
+    // DIVBAD's 2025-06-10 dividend amount is "abc": strict mode must not
+    // count the stock as paying nothing.
+    let error = calculate_portfolio_performance_with_options(
+        &score_path.to_string_lossy(),
+        "2025-06-01",
+        &strict_performance(),
+    )
+    .unwrap_err();
+    assert!(matches!(error, GrqError::StrictModeViolation(_)), "{error}");
+    assert!(
+        error.to_string().contains("dividend amount 'abc'"),
+        "{error}"
+    );
+
+    // The projection of a young score credits dividends the same way.
+    let closes = [(
+        "NYSE:DIVBAD".to_string(),
+        [(date(2025, 6, 2), 20.0), (date(2025, 6, 20), 21.0)]
+            .into_iter()
+            .collect(),
+    )]
+    .into_iter()
+    .collect();
+    let projection = ProjectionOptions {
+        as_of: Some(date(2025, 6, 20)),
+        ..ProjectionOptions::default()
+    };
+    let error = calculate_hybrid_projection_with_options(
+        &[StockRecord::new("NYSE:DIVBAD".to_string(), 1.0, 24.0)],
+        "2025-06-01",
+        &closes,
+        &projection,
+        &strict_performance(),
+    )
+    .unwrap_err();
+    assert!(matches!(error, GrqError::StrictModeViolation(_)), "{error}");
+}
+
+#[test]
+fn test_strict_performance_accepts_a_declared_dividend_outside_the_window() {
+    let docs = tempfile::tempdir().unwrap();
+    let score_path = docs.path().join("01.tsv");
+    std::fs::write(
+        &score_path,
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:PAYER\t1.0\t$36.00\t\t0.50\t\t\t\n",
+    )
+    .unwrap();
+    std::fs::write(
+        docs.path().join("01.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-06-02,NYSE:PAYER,0,0,0,30.0\n\
+         2025-08-29,NYSE:PAYER,0,0,0,33.0\n",
+    )
+    .unwrap();
+
+    // PAYER's dividend data exists; its next ex-date is just not in the window.
+    let performance = calculate_portfolio_performance_with_options(
+        &score_path.to_string_lossy(),
+        "2025-06-01",
+        &strict_performance(),
+    )
+    .unwrap();
+    assert!((performance.performance_90_day - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_strict_index_update_fails_instead_of_skipping_the_entry() {
+    let docs = tempfile::tempdir().unwrap();
+    let scores = docs.path().join("scores");
+    std::fs::create_dir_all(&scores).unwrap();
+    std::fs::write(
+        scores.join("01.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:GOOD\t1.0\t$12.00\t\t0.25\t\t\t\n",
+    )
+    .unwrap();
+    std::fs::write(
+        scores.join("01.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-06-02,NYSE:GOOD,0,0,0,10.0\n\
+         2025-08-29,NYSE:GOOD,0,0,0,11.0\n",
+    )
+    .unwrap();
+    let index = r#"{"scores": [{"year": "2025", "month": "June", "day": "1",
+        "file": "01.tsv", "date": "2025-06-01"}]}"#;
+    std::fs::write(scores.join("index.json"), index).unwrap();
+
+    // The default skip policy would log and move on; strict mode must not.
+    let options = IndexUpdateOptions {
+        performance: strict_performance(),
+        ..IndexUpdateOptions::default()
+    };
+    let error =
+        update_index_with_performance_with_options(&docs.path().to_string_lossy(), &options)
+            .unwrap_err();
+    assert!(matches!(error, GrqError::StrictModeViolation(_)), "{error}");
+    assert_eq!(
+        std::fs::read_to_string(scores.join("index.json")).unwrap(),
+        index
+    );
+
+    // A month in, the entry is projected instead; a malformed close in its
+    // market CSV must fail the update the same way.
+    std::fs::write(
+        scores.join("01.csv"),
+        "date,ticker,high,low,open,close
+         2025-06-02,NYSE:GOOD,0,0,0,10.0
+         2025-06-20,NYSE:GOOD,0,0,0,N/A
+",
+    )
+    .unwrap();
+    let young = IndexUpdateOptions {
+        projection: ProjectionOptions {
+            clock: Arc::new(FixedClock(
+                chrono::NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+            )),
+            ..ProjectionOptions::default()
+        },
+        ..options
+    };
+    let error = update_index_with_performance_with_options(&docs.path().to_string_lossy(), &young)
+        .unwrap_err();
+    assert!(matches!(error, GrqError::StrictModeViolation(_)), "{error}");
+    assert_eq!(
+        std::fs::read_to_string(scores.join("index.json")).unwrap(),
+        index
+    );
+}
@@ -0,0 +1,92 @@
+//! A renamed ticker whose history is split across two price files.
+//!
+mod common;
+
+use common::write_prices;
+use grq_validation::utils::{
+    read_market_data_window, read_market_data_with_options, read_symbol_aliases, DataOptions,
+};
+use std::sync::OnceLock;
+
+/// Sets up the shared archive once: `FB` trades until 2022-06-08 and `META`
+/// from 2022-06-09, with `FB → META` read from an alias file. `ONLYOLD` is
+/// renamed to `GONE`, which has no file. Returns data options reading it.
+fn renamed_archive() -> &'static DataOptions {
+    static ARCHIVE: OnceLock<(tempfile::TempDir, DataOptions)> = OnceLock::new();
+    &ARCHIVE
+        .get_or_init(|| {
+            let archive = tempfile::tempdir().unwrap();
+            write_prices(
+                archive.path(),
+                "FB",
+                "2022-06-08",
+                &[("2022-06-07", "195.0"), ("2022-06-08", "196.0")],
+            );
+            write_prices(
+                archive.path(),
+                "META",
+                "2022-06-10",
+                &[("2022-06-09", "198.0"), ("2022-06-10", "184.0")],
+            );
+            write_prices(
+                archive.path(),
+                "ONLYOLD",
+                "2022-06-08",
+                &[("2022-06-08", "10.0")],
+            );
+            let aliases = archive.path().join("aliases.json");
+            std::fs::write(&aliases, r#"{"FB": "META", "ONLYOLD": "GONE"}"#).unwrap();
+
+            let data = DataOptions {
+                market_data_paths: vec![archive.path().to_path_buf()],
+                symbol_aliases: read_symbol_aliases(&aliases).unwrap(),
+                ..DataOptions::default()
+            };
+            (archive, data)
+        })
+        .1
+}
+
+fn dates(symbol: &str) -> Vec<String> {
+    let mut dates: Vec<String> = read_market_data_with_options(symbol, renamed_archive())
+        .unwrap()
+        .time_series_daily
+        .into_keys()
+        .collect();
+    dates.sort();
+    dates
+}
+
+#[test]
+fn test_either_symbol_reads_the_stitched_history() {
+    let data = renamed_archive();
+    let stitched = ["2022-06-07", "2022-06-08", "2022-06-09", "2022-06-10"];
+
+    assert_eq!(dates("FB"), stitched);
+    assert_eq!(dates("META"), stitched);
+    assert_eq!(
+        read_market_data_with_options("FB", data)
+            .unwrap()
+            .meta_data
+            .last_refreshed,
+        "2022-06-10"
+    );
+
+    let window = read_market_data_window(
+        "NASDAQ:META",
+        "2022-06-08".parse().unwrap(),
+        "2022-06-09".parse().unwrap(),
+        data,
+    )
+    .unwrap();
+    let mut window_dates: Vec<_> = window.time_series_daily.into_keys().collect();
+    window_dates.sort();
+    assert_eq!(window_dates, ["2022-06-08", "2022-06-09"]);
+}
+
+#[test]
+fn test_alias_without_a_file_leaves_the_symbol_as_it_is() {
+    assert_eq!(dates("ONLYOLD"), ["2022-06-08"]);
+    assert_eq!(dates("GONE"), ["2022-06-08"]);
+    assert!(read_market_data_with_options("UNRELATED", renamed_archive()).is_err());
+}
@@ -13,10 +13,15 @@
 //!   * a still-open (< 90 day old) score whose source data is absent keeps
 //!     `performance_90_day == null` rather than fabricating a figure.
 
-use chrono::{Duration, Utc};
-use grq_validation::utils::{read_index_json, update_index_with_performance};
+use chrono::{Duration, NaiveDate};
+use grq_validation::utils::{
+    read_index_json, update_index_with_performance, update_index_with_performance_with_options,
+    ErrorPolicy, FixedClock, IndexUpdateOptions, PerformanceOptions, ProjectionOptions,
+    MAX_SANITIZED_VALUE,
+};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Writes `contents` to `path`, creating parent directories as needed.
 fn write_file(path: &Path, contents: &str) {
@@ -26,12 +31,29 @@ fn write_file(path: &Path, contents: &str) {
     fs::write(path, contents).expect("write fixture file");
 }
 
-/// A `YYYY-MM-DD` date string `days` before today (UTC).
+/// The date these tests treat as today, so which entries are settled does
+/// not depend on the day they run.
+fn today() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()
+}
+
+/// A `YYYY-MM-DD` date string `days` before [`today`].
 fn date_days_ago(days: i64) -> String {
-    let date = Utc::now().naive_utc().date() - Duration::days(days);
+    let date = today() - Duration::days(days);
     date.format("%Y-%m-%d").to_string()
 }
 
+/// The default update options, dated by a `FixedClock` at [`today`].
+fn fixed_clock_options() -> IndexUpdateOptions {
+    IndexUpdateOptions {
+        projection: ProjectionOptions {
+            clock: Arc::new(FixedClock(today())),
+            ..ProjectionOptions::default()
+        },
+        ..IndexUpdateOptions::default()
+    }
+}
+
 #[test]
 fn update_index_with_performance_writes_settled_and_open_entries() {
     let dir = tempfile::tempdir().expect("create temp docs dir");
@@ -86,7 +108,7 @@ fn update_index_with_performance_writes_settled_and_open_entries() {
     write_file(&scores.join("index.json"), &index_json);
 
     // Act: run the real write path against the fixture.
-    update_index_with_performance(docs.to_str().unwrap())
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &fixed_clock_options())
         .expect("update_index_with_performance should succeed");
 
     // Assert on the persisted output, located by `file` rather than position.
@@ -134,3 +156,602 @@ fn update_index_with_performance_writes_settled_and_open_entries() {
         "still-open score must keep performance_annualized = null"
     );
 }
+
+#[test]
+fn incremental_update_skips_settled_entries_only() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+
+    // Both entries are settled (> 90 days old) and have the same TSV + CSV
+    // sources, which would compute 10%. The first already records a settled
+    // figure (with its 90-day horizon) and must be left alone; the second only
+    // carries an old hybrid projection (no horizon) and must be recomputed.
+    for (file, score_date) in [
+        ("2025/January/15.tsv", "2025-01-15"),
+        ("2025/January/16.tsv", "2025-01-16"),
+    ] {
+        write_file(
+            &scores.join(file),
+            "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+             intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+             NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+        );
+        write_file(
+            &scores.join(file.replace(".tsv", ".csv")),
+            &format!(
+                "date,ticker,high,low,open,close\n\
+                 {score_date},NYSE:TEST,0,0,0,100.0\n\
+                 2025-04-15,NYSE:TEST,0,0,0,110.0\n"
+            ),
+        );
+    }
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15",
+      "performance_90_day": 42.0, "return_90d": 42.0
+    },
+    {
+      "year": "2025", "month": "January", "day": "16",
+      "file": "2025/January/16.tsv", "date": "2025-01-16",
+      "performance_90_day": 42.0
+    }
+  ]
+}"#,
+    );
+
+    let options = IndexUpdateOptions {
+        incremental: true,
+        ..IndexUpdateOptions::default()
+    };
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+        .expect("incremental update should succeed");
+
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].performance_90_day, Some(42.0));
+    let recomputed = updated.scores[1]
+        .performance_90_day
+        .expect("projection entry recomputed");
+    assert!(
+        (recomputed - 10.0).abs() < 1e-6,
+        "expected the settled 10% figure, got {recomputed}"
+    );
+}
+
+#[test]
+fn projected_entry_switches_to_settled_once_it_matures() {
+    // A score `age` days old whose index entry already carries `recorded`,
+    // priced +5% at day 30 and +10% at day 90 when those days have passed.
+    let run = |age: i64, recorded: &str| {
+        let dir = tempfile::tempdir().expect("create temp docs dir");
+        let docs = dir.path();
+        let scores = docs.join("scores");
+        let score_date = today() - Duration::days(age);
+        write_file(
+            &scores.join("score.tsv"),
+            "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+             intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+             NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+        );
+        let mut csv = String::from("date,ticker,high,low,open,close\n");
+        for (day, close) in [(0, 100.0), (30, 105.0), (90, 110.0)] {
+            if day <= age {
+                let date = score_date + Duration::days(day);
+                csv.push_str(&format!("{date},NYSE:TEST,0,0,0,{close}\n"));
+            }
+        }
+        write_file(&scores.join("score.csv"), &csv);
+        write_file(
+            &scores.join("index.json"),
+            &format!(
+                r#"{{
+  "scores": [
+    {{
+      "year": "2025", "month": "January", "day": "15",
+      "file": "score.tsv", "date": "{score_date}"{recorded}
+    }}
+  ]
+}}"#
+            ),
+        );
+
+        let options = IndexUpdateOptions {
+            incremental: true,
+            ..fixed_clock_options()
+        };
+        update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+            .expect("update should succeed");
+        read_index_json(docs.to_str().unwrap())
+            .expect("re-read rewritten index")
+            .scores
+            .remove(0)
+    };
+
+    // Day 89: still a projection, which carries no horizon returns.
+    let projected = run(89, "");
+    let projection = projected.performance_90_day.expect("projection recorded");
+    assert!(
+        (projection - 10.0).abs() > 1e-6,
+        "day 89 should be projected, got the settled {projection}"
+    );
+    assert_eq!(projected.return_90d, None);
+
+    // Day 91: the settled calculation replaces the recorded projection.
+    let settled = run(91, &format!(r#", "performance_90_day": {projection}"#));
+    let performance = settled.performance_90_day.expect("settled figure recorded");
+    assert!(
+        (performance - 10.0).abs() < 1e-6,
+        "expected the settled 10% figure, got {performance}"
+    );
+    assert!(settled.return_90d.is_some());
+}
+
+#[test]
+fn fail_fast_aborts_without_rewriting_the_index() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    // The score file for this settled entry does not exist.
+    let index = r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    }
+  ]
+}"#;
+    write_file(&docs.join("scores").join("index.json"), index);
+
+    let options = IndexUpdateOptions {
+        error_policy: ErrorPolicy::FailFast,
+        ..IndexUpdateOptions::default()
+    };
+    assert!(update_index_with_performance_with_options(docs.to_str().unwrap(), &options).is_err());
+    assert_eq!(
+        fs::read_to_string(docs.join("scores").join("index.json")).unwrap(),
+        index
+    );
+
+    // The default policy skips the broken entry and still rewrites the index.
+    update_index_with_performance_with_options(
+        docs.to_str().unwrap(),
+        &IndexUpdateOptions::default(),
+    )
+    .expect("skip policy should succeed");
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert!(updated.scores[0].performance_90_day.is_none());
+}
+
+#[test]
+fn a_bad_date_or_missing_file_is_skipped_and_later_entries_still_update() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    write_file(
+        &scores.join("2025/January/16.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    write_file(
+        &scores.join("2025/January/16.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-16,NYSE:TEST,0,0,0,100.0\n\
+         2025-04-16,NYSE:TEST,0,0,0,110.0\n",
+    );
+    // The first entry's date cannot be parsed and the second's file is gone;
+    // neither may stop the third from being updated.
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "14",
+      "file": "2025/January/14.tsv", "date": "2025-01-xx"
+    },
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    },
+    {
+      "year": "2025", "month": "January", "day": "16",
+      "file": "2025/January/16.tsv", "date": "2025-01-16"
+    }
+  ]
+}"#,
+    );
+
+    update_index_with_performance(docs.to_str().unwrap())
+        .expect("one bad entry should not fail the run");
+
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    let performance = |date: &str| {
+        updated
+            .scores
+            .iter()
+            .find(|entry| entry.date == date)
+            .expect("entry kept")
+            .performance_90_day
+    };
+    assert_eq!(performance("2025-01-xx"), None);
+    assert_eq!(performance("2025-01-15"), None);
+    let settled = performance("2025-01-16").expect("later entry updated");
+    assert!((settled - 10.0).abs() < 1e-6, "got {settled}");
+}
+
+#[test]
+fn only_dates_recomputes_just_the_listed_entries() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    for day in ["15", "16"] {
+        write_file(
+            &scores.join(format!("2025/January/{day}.tsv")),
+            "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+             intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+             NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+        );
+        write_file(
+            &scores.join(format!("2025/January/{day}.csv")),
+            &format!(
+                "date,ticker,high,low,open,close\n\
+                 2025-01-{day},NYSE:TEST,0,0,0,100.0\n\
+                 2025-04-{day},NYSE:TEST,0,0,0,110.0\n"
+            ),
+        );
+    }
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15",
+      "performance_90_day": 42.0
+    },
+    {
+      "year": "2025", "month": "January", "day": "16",
+      "file": "2025/January/16.tsv", "date": "2025-01-16",
+      "performance_90_day": 42.0
+    }
+  ]
+}"#,
+    );
+
+    let options = IndexUpdateOptions {
+        only_dates: Some([chrono::NaiveDate::from_ymd_opt(2025, 1, 16).unwrap()].into()),
+        ..IndexUpdateOptions::default()
+    };
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+        .expect("update should succeed");
+
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].performance_90_day, Some(42.0));
+    let recomputed = updated.scores[1]
+        .performance_90_day
+        .expect("listed entry recomputed");
+    assert!(
+        (recomputed - 10.0).abs() < 1e-6,
+        "expected the settled 10% figure, got {recomputed}"
+    );
+}
+
+#[test]
+fn an_out_of_order_index_keeps_its_file_order() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    for day in ["15", "16"] {
+        write_file(
+            &scores.join(format!("2025/January/{day}.tsv")),
+            "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+             intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+             NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+        );
+        write_file(
+            &scores.join(format!("2025/January/{day}.csv")),
+            &format!(
+                "date,ticker,high,low,open,close\n\
+                 2025-01-{day},NYSE:TEST,0,0,0,100.0\n\
+                 2025-04-{day},NYSE:TEST,0,0,0,110.0\n"
+            ),
+        );
+    }
+    // Newest first, as a hand-edited index might be.
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "16",
+      "file": "2025/January/16.tsv", "date": "2025-01-16"
+    },
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    }
+  ]
+}"#,
+    );
+
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &fixed_clock_options())
+        .expect("update should succeed");
+
+    let written = fs::read_to_string(scores.join("index.json")).expect("read rewritten index");
+    let persisted: serde_json::Value = serde_json::from_str(&written).expect("valid JSON");
+    let dates: Vec<&str> = persisted["scores"]
+        .as_array()
+        .expect("scores array")
+        .iter()
+        .map(|entry| entry["date"].as_str().expect("date string"))
+        .collect();
+    assert_eq!(dates, ["2025-01-16", "2025-01-15"]);
+    for entry in persisted["scores"].as_array().unwrap() {
+        let performance = entry["performance_90_day"]
+            .as_f64()
+            .expect("performance recorded");
+        assert!((performance - 10.0).abs() < 1e-6, "got {performance}");
+    }
+}
+
+#[test]
+fn too_few_stocks_leaves_the_entry_unset_even_under_fail_fast() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    write_file(
+        &scores.join("2025/January/15.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n\
+         NYSE:NODATA\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    write_file(
+        &scores.join("2025/January/15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:TEST,0,0,0,100.0\n\
+         2025-04-15,NYSE:TEST,0,0,0,110.0\n",
+    );
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15",
+      "performance_90_day": 42.0, "performance_annualized": 300.0, "total_stocks": 1
+    }
+  ]
+}"#,
+    );
+
+    let options = IndexUpdateOptions {
+        performance: PerformanceOptions {
+            min_stocks: 2,
+            ..PerformanceOptions::default()
+        },
+        error_policy: ErrorPolicy::FailFast,
+        ..IndexUpdateOptions::default()
+    };
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+        .expect("a small portfolio is not a failure");
+
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].performance_90_day, None);
+    assert_eq!(updated.scores[0].performance_annualized, None);
+    assert_eq!(updated.scores[0].total_stocks, None);
+
+    // The one priced stock is enough without the threshold.
+    update_index_with_performance_with_options(
+        docs.to_str().unwrap(),
+        &IndexUpdateOptions::default(),
+    )
+    .expect("update should succeed");
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].total_stocks, Some(1));
+}
+
+#[test]
+fn an_as_of_replay_leaves_the_index_unchanged() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    write_file(
+        &scores.join("2025/January/15.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    write_file(
+        &scores.join("2025/January/15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:TEST,0,0,0,100.0\n\
+         2025-04-15,NYSE:TEST,0,0,0,110.0\n",
+    );
+    let index = r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    }
+  ]
+}"#;
+    write_file(&scores.join("index.json"), index);
+
+    let options = IndexUpdateOptions {
+        projection: ProjectionOptions {
+            as_of: NaiveDate::from_ymd_opt(2025, 3, 1),
+            ..ProjectionOptions::default()
+        },
+        ..IndexUpdateOptions::default()
+    };
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+        .expect("replay should succeed");
+
+    assert_eq!(
+        fs::read_to_string(scores.join("index.json")).unwrap(),
+        index
+    );
+}
+
+#[test]
+fn performance_options_reach_the_index_update() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    write_file(
+        &scores.join("2025/January/15.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:LATE\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    // The first close is 20 days after the score date: past the default buy
+    // gap, within a 30-day one.
+    write_file(
+        &scores.join("2025/January/15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-02-04,NYSE:LATE,0,0,0,100.0\n\
+         2025-04-15,NYSE:LATE,0,0,0,110.0\n",
+    );
+    let index = r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    }
+  ]
+}"#;
+
+    let run = |options: &IndexUpdateOptions| {
+        write_file(&scores.join("index.json"), index);
+        update_index_with_performance_with_options(docs.to_str().unwrap(), options)
+            .expect("update should succeed");
+        read_index_json(docs.to_str().unwrap())
+            .expect("re-read rewritten index")
+            .scores
+            .remove(0)
+            .performance_90_day
+    };
+
+    // The default gap cannot buy the stock, so it earns none of the 10%.
+    let default = run(&IndexUpdateOptions::default());
+    assert!(
+        default.is_none_or(|performance| (performance - 10.0).abs() > 1e-6),
+        "the default gap should exclude the late buy, got {default:?}"
+    );
+    let widened = IndexUpdateOptions {
+        performance: PerformanceOptions {
+            max_buy_gap_days: 30,
+            ..PerformanceOptions::default()
+        },
+        ..IndexUpdateOptions::default()
+    };
+    let performance = run(&widened).expect("late buy admitted");
+    assert!(
+        (performance - 10.0).abs() < 1e-6,
+        "expected 10%, got {performance}"
+    );
+}
+
+#[test]
+fn too_few_stocks_leaves_a_projected_entry_unset_too() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    let score_date = today() - Duration::days(30);
+    write_file(
+        &scores.join("young.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TEST\t1.0\t150.00\t\t\t\t\t\n\
+         NYSE:NODATA\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    write_file(
+        &scores.join("young.csv"),
+        &format!(
+            "date,ticker,high,low,open,close\n\
+             {score_date},NYSE:TEST,0,0,0,100.0\n\
+             {},NYSE:TEST,0,0,0,105.0\n",
+            score_date + Duration::days(20)
+        ),
+    );
+    write_file(
+        &scores.join("index.json"),
+        &format!(
+            r#"{{
+  "scores": [
+    {{
+      "year": "2025", "month": "January", "day": "15",
+      "file": "young.tsv", "date": "{score_date}",
+      "performance_90_day": 42.0, "performance_annualized": 300.0, "total_stocks": 1
+    }}
+  ]
+}}"#
+        ),
+    );
+
+    let options = IndexUpdateOptions {
+        performance: PerformanceOptions {
+            min_stocks: 2,
+            ..PerformanceOptions::default()
+        },
+        error_policy: ErrorPolicy::FailFast,
+        ..fixed_clock_options()
+    };
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &options)
+        .expect("a small portfolio is not a failure");
+
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].performance_90_day, None);
+    assert_eq!(updated.scores[0].total_stocks, None);
+
+    // The one projected stock is enough without the threshold.
+    update_index_with_performance_with_options(docs.to_str().unwrap(), &fixed_clock_options())
+        .expect("update should succeed");
+    let updated = read_index_json(docs.to_str().unwrap()).expect("re-read rewritten index");
+    assert_eq!(updated.scores[0].total_stocks, Some(1));
+}
+
+#[test]
+fn an_overflowing_return_is_bounded_in_the_index() {
+    let dir = tempfile::tempdir().expect("create temp docs dir");
+    let docs = dir.path();
+    let scores = docs.join("scores");
+    write_file(
+        &scores.join("2025/January/15.tsv"),
+        "Stock\tScore\tTarget\tExDividendDate\tDividendPerShare\tNotes\t\
+         intrinsicValuePerShareBasic\tintrinsicValuePerShareAdjusted\n\
+         NYSE:TINY\t1.0\t150.00\t\t\t\t\t\n",
+    );
+    // A near-zero buy price: the 90-day return is finite but enormous, and
+    // annualising it overflows.
+    write_file(
+        &scores.join("2025/January/15.csv"),
+        "date,ticker,high,low,open,close\n\
+         2025-01-15,NYSE:TINY,0,0,0,1e-300\n\
+         2025-04-15,NYSE:TINY,0,0,0,10.0\n",
+    );
+    write_file(
+        &scores.join("index.json"),
+        r#"{
+  "scores": [
+    {
+      "year": "2025", "month": "January", "day": "15",
+      "file": "2025/January/15.tsv", "date": "2025-01-15"
+    }
+  ]
+}"#,
+    );
+
+    update_index_with_performance(docs.to_str().unwrap()).expect("update should succeed");
+
+    let entry = read_index_json(docs.to_str().unwrap())
+        .expect("re-read rewritten index")
+        .scores
+        .remove(0);
+    assert_eq!(entry.performance_90_day, Some(MAX_SANITIZED_VALUE));
+    assert_eq!(entry.performance_annualized, Some(MAX_SANITIZED_VALUE));
+}